@@ -0,0 +1,83 @@
+use castep_periodic_table::{
+    data::{element, element_by_atomic_number, ELEMENTS},
+    element::{Element, ElementSymbol, LookupElement},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// A species list like a large supercell's, cycling through every element.
+fn species(n: usize) -> Vec<ElementSymbol> {
+    ELEMENTS
+        .iter()
+        .map(|elm| elm.symbol())
+        .cycle()
+        .take(n)
+        .collect()
+}
+
+fn linear_find(table: &[Element; 103], symbol: ElementSymbol) -> &Element {
+    table.iter().find(|elm| elm.symbol() == symbol).unwrap()
+}
+
+fn bench_symbol_lookup(c: &mut Criterion) {
+    let species = species(100_000);
+    let mut group = c.benchmark_group("symbol lookup, 100k atoms");
+    group.bench_function("linear find", |b| {
+        b.iter(|| {
+            species
+                .iter()
+                .map(|&symbol| linear_find(&ELEMENTS, black_box(symbol)).mass())
+                .sum::<f64>()
+        })
+    });
+    group.bench_function("LookupElement::get_by_symbol", |b| {
+        b.iter(|| {
+            species
+                .iter()
+                .map(|&symbol| ELEMENTS.get_by_symbol(black_box(symbol)).mass())
+                .sum::<f64>()
+        })
+    });
+    group.bench_function("data::element", |b| {
+        b.iter(|| {
+            species
+                .iter()
+                .map(|&symbol| element(black_box(symbol)).mass())
+                .sum::<f64>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_atomic_number_lookup(c: &mut Criterion) {
+    let numbers: Vec<u8> = species(100_000)
+        .iter()
+        .map(|&symbol| element(symbol).atomic_number())
+        .collect();
+    let mut group = c.benchmark_group("atomic number lookup, 100k atoms");
+    group.bench_function("linear find", |b| {
+        b.iter(|| {
+            numbers
+                .iter()
+                .filter_map(|&n| {
+                    ELEMENTS
+                        .iter()
+                        .find(|elm| elm.atomic_number() == black_box(n))
+                })
+                .map(|elm| elm.mass())
+                .sum::<f64>()
+        })
+    });
+    group.bench_function("data::element_by_atomic_number", |b| {
+        b.iter(|| {
+            numbers
+                .iter()
+                .filter_map(|&n| element_by_atomic_number(black_box(n)))
+                .map(|elm| elm.mass())
+                .sum::<f64>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_symbol_lookup, bench_atomic_number_lookup);
+criterion_main!(benches);
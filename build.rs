@@ -0,0 +1,27 @@
+//! Generates `ELEMENT_TABLE` and the `ElementSymbol` lookups from
+//! `element_table.yaml`.
+use std::{env, fs, path::PathBuf};
+
+#[path = "src/element/codegen.rs"]
+mod codegen;
+
+fn main() {
+    println!("cargo:rerun-if-changed=element_table.yaml");
+    println!("cargo:rerun-if-changed=src/element/codegen.rs");
+    let yaml = fs::read_to_string("element_table.yaml").expect("failed to read element_table.yaml");
+    let table: codegen::RawElementTable =
+        serde_yaml::from_str(&yaml).unwrap_or_else(|e| panic!("element_table.yaml: {e}"));
+    if let Err(e) = codegen::validate(&table.elements) {
+        panic!("element_table.yaml: {e}");
+    }
+    let element_table = codegen::element_table(&table.elements)
+        .unwrap_or_else(|e| panic!("element_table.yaml: {e}"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::write(out_dir.join("element_table.rs"), element_table)
+        .expect("failed to write element_table.rs");
+    fs::write(
+        out_dir.join("element_symbol.rs"),
+        codegen::symbol_lookup(&table.elements),
+    )
+    .expect("failed to write element_symbol.rs");
+}
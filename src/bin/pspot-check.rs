@@ -0,0 +1,139 @@
+//! Check that the pseudopotential files named in the element table exist.
+//!
+//! ```text
+//! pspot-check [--dir DIR] [--recursive] [--set NAME] [--overrides FILE]... [SYMBOL]...
+//! ```
+//! The directory defaults to `$CASTEP_PSPOT_DIR`. `--recursive` also lists
+//! the potentials in its subdirectories as alternatives. `--set` selects a named
+//! potential set, e.g. `00PBE`, before the overrides are applied. Without symbols every
+//! element is checked. Potentials of one element and kind found more than
+//! once are listed as `duplicate` lines.
+//!
+//! Exit status: 0 when every file is found once, 1 when a file is missing or
+//! also found in a subdirectory, 2 on invalid arguments or an unreadable
+//! directory. Other duplicates, such as an LDA and a PBE potential of the
+//! same element, are listed but do not fail the check.
+use std::{path::PathBuf, process::ExitCode, str::FromStr};
+
+use castep_periodic_table::{
+    element::{ElementSymbol, ElementTable, PotentialSet},
+    pspot::{PotentialIndex, ScanError, PSPOT_DIR_VAR},
+};
+
+const USAGE: &str =
+    "usage: pspot-check [--dir DIR] [--recursive] [--set NAME] [--overrides FILE]... [SYMBOL]...";
+
+struct Args {
+    help: bool,
+    dir: Option<PathBuf>,
+    recursive: bool,
+    set: Option<PotentialSet>,
+    overrides: Vec<PathBuf>,
+    species: Vec<ElementSymbol>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        help: false,
+        dir: None,
+        recursive: false,
+        set: None,
+        overrides: Vec::new(),
+        species: Vec::new(),
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--dir" => args.dir = Some(argv.next().ok_or("--dir needs a value")?.into()),
+            "--recursive" => args.recursive = true,
+            "--set" => {
+                let name = argv.next().ok_or("--set needs a value")?;
+                args.set = Some(name.parse().map_err(|e| format!("{e}"))?);
+            }
+            "--overrides" => args
+                .overrides
+                .push(argv.next().ok_or("--overrides needs a value")?.into()),
+            "-h" | "--help" => args.help = true,
+            symbol => args
+                .species
+                .push(ElementSymbol::from_str(symbol).map_err(|e| format!("{symbol}: {e}"))?),
+        }
+    }
+    Ok(args)
+}
+
+fn run() -> Result<bool, String> {
+    let args = parse_args()?;
+    if args.help {
+        println!("{USAGE}");
+        return Ok(true);
+    }
+    let mut table = ElementTable::builtin();
+    if let Some(set) = args.set.as_ref() {
+        let missing: Vec<String> = set
+            .missing()
+            .filter(|symbol| args.species.is_empty() || args.species.contains(symbol))
+            .map(|symbol| symbol.to_string())
+            .collect();
+        if !missing.is_empty() {
+            eprintln!(
+                "warning: {} has no potential for {}",
+                set.name(),
+                missing.join(", ")
+            );
+        }
+        table = table.with_potential_set(set);
+    }
+    for path in args.overrides.iter() {
+        table = table.with_override_file(path).map_err(|e| e.to_string())?;
+    }
+    let potentials = PotentialSet::from_table("checked", &table);
+    let warning = if args.species.is_empty() {
+        potentials.mixed_functionals()
+    } else {
+        potentials.mixed_functionals_for(&args.species)
+    };
+    if let Some(warning) = warning {
+        eprintln!("warning: {warning}");
+    }
+    let index = match (args.dir, args.recursive) {
+        (Some(dir), false) => PotentialIndex::scan(dir),
+        (Some(dir), true) => PotentialIndex::scan_recursive(dir),
+        (None, false) => PotentialIndex::from_env(),
+        (None, true) => std::env::var_os(PSPOT_DIR_VAR)
+            .ok_or(ScanError::NotSet)
+            .and_then(PotentialIndex::scan_recursive),
+    }
+    .map_err(|e| format!("{e}\n{USAGE} (or set {PSPOT_DIR_VAR})"))?;
+    let report = if args.species.is_empty() {
+        index.check(&table)
+    } else {
+        index.check_species(&table, &args.species)
+    };
+    print!("{report}");
+    let duplicates = index.duplicates().into_iter().filter(|duplicate| {
+        args.species.is_empty() || args.species.contains(&duplicate[0].element)
+    });
+    for duplicate in duplicates {
+        let paths: Vec<String> = duplicate
+            .iter()
+            .map(|potential| potential.path.display().to_string())
+            .collect();
+        println!("duplicate {}: {}", duplicate[0].element, paths.join(", "));
+    }
+    for path in index.unrecognised() {
+        println!("unrecognised {}", path.display());
+    }
+    Ok(report.is_complete())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,646 @@
+//! Bonds and coordination numbers from covalent radii, for molecules and
+//! periodic cells.
+//!
+//! Two atoms are bonded when their distance is at most the sum of their
+//! covalent radii times a tolerance factor. Lengths are in angstrom and
+//! lattice vectors are rows, as in the `LATTICE_CART` block.
+//!
+//! ```
+//! use castep_periodic_table::bonding::{BondDetector, Lattice, Structure};
+//! use castep_periodic_table::data::ELEMENT_TABLE;
+//! use castep_periodic_table::element::ElementSymbol;
+//!
+//! // Diamond: every carbon has four neighbours, two of them in other cells.
+//! let a = 3.567;
+//! let lattice = Lattice::new([[0.0, a / 2.0, a / 2.0], [a / 2.0, 0.0, a / 2.0], [a / 2.0, a / 2.0, 0.0]]);
+//! let diamond = Structure::from_fractional(
+//!     lattice,
+//!     [(ElementSymbol::C, [0.0; 3]), (ElementSymbol::C, [0.25; 3])],
+//! )
+//! .unwrap();
+//! let detector = BondDetector::new(&ELEMENT_TABLE);
+//! assert_eq!(detector.coordination_numbers(&diamond).unwrap(), [4, 4]);
+//! let bond = &detector.bonds(&diamond).unwrap()[0];
+//! assert!((bond.length - 1.5446).abs() < 1e-4);
+//! ```
+
+use std::fmt::Display;
+
+use crate::{
+    element::{ElementSymbol, LookupElement},
+    magnetism::Site,
+    radius::{radius, RadiusKind},
+};
+
+/// Lattice vectors `a`, `b` and `c` as rows, in angstrom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lattice {
+    vectors: [[f64; 3]; 3],
+}
+
+impl Lattice {
+    pub fn new(vectors: [[f64; 3]; 3]) -> Self {
+        Self { vectors }
+    }
+
+    pub fn vectors(&self) -> [[f64; 3]; 3] {
+        self.vectors
+    }
+
+    pub fn volume(&self) -> f64 {
+        let [a, b, c] = self.vectors;
+        dot(a, cross(b, c))
+    }
+
+    pub fn to_cartesian(&self, fractional: [f64; 3]) -> [f64; 3] {
+        let mut cartesian = [0.0; 3];
+        for (f, vector) in fractional.iter().zip(self.vectors.iter()) {
+            for (x, v) in cartesian.iter_mut().zip(vector.iter()) {
+                *x += f * v;
+            }
+        }
+        cartesian
+    }
+
+    /// Reciprocal vectors without the factor 2 pi, so that
+    /// `fractional[i] = reciprocal[i] . cartesian`.
+    fn reciprocal(&self) -> Result<[[f64; 3]; 3], BondError> {
+        let volume = self.volume();
+        if volume.abs() < 1e-12 {
+            return Err(BondError::SingularLattice);
+        }
+        let [a, b, c] = self.vectors;
+        Ok([cross(b, c), cross(c, a), cross(a, b)].map(|v| v.map(|x| x / volume)))
+    }
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// An atom at a Cartesian position in angstrom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Atom {
+    pub symbol: ElementSymbol,
+    pub position: [f64; 3],
+}
+
+/// Atoms of a molecule, or of a periodic cell when there is a lattice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Structure {
+    lattice: Option<Lattice>,
+    atoms: Vec<Atom>,
+}
+
+impl Structure {
+    /// A structure without periodic images.
+    pub fn molecule<I>(atoms: I) -> Self
+    where
+        I: IntoIterator<Item = (ElementSymbol, [f64; 3])>,
+    {
+        Self {
+            lattice: None,
+            atoms: atoms
+                .into_iter()
+                .map(|(symbol, position)| Atom { symbol, position })
+                .collect(),
+        }
+    }
+
+    /// A periodic cell with atoms at Cartesian positions.
+    pub fn periodic<I>(lattice: Lattice, atoms: I) -> Result<Self, BondError>
+    where
+        I: IntoIterator<Item = (ElementSymbol, [f64; 3])>,
+    {
+        lattice.reciprocal()?;
+        Ok(Self {
+            lattice: Some(lattice),
+            ..Self::molecule(atoms)
+        })
+    }
+
+    /// A periodic cell with atoms at fractional positions.
+    pub fn from_fractional<I>(lattice: Lattice, atoms: I) -> Result<Self, BondError>
+    where
+        I: IntoIterator<Item = (ElementSymbol, [f64; 3])>,
+    {
+        Self::periodic(
+            lattice,
+            atoms
+                .into_iter()
+                .map(|(symbol, fractional)| (symbol, lattice.to_cartesian(fractional))),
+        )
+    }
+
+    /// The sites of a `POSITIONS_FRAC` block.
+    pub fn from_sites(lattice: Lattice, sites: &[Site]) -> Result<Self, BondError> {
+        Self::from_fractional(
+            lattice,
+            sites.iter().map(|site| (site.symbol(), site.position)),
+        )
+    }
+
+    pub fn lattice(&self) -> Option<&Lattice> {
+        self.lattice.as_ref()
+    }
+
+    pub fn atoms(&self) -> &[Atom] {
+        &self.atoms
+    }
+}
+
+/// A bond from atom `i` to atom `j` in the cell shifted by `image` lattice
+/// vectors. Each bond is listed once, with `i <= j`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bond {
+    pub i: usize,
+    pub j: usize,
+    pub image: [i32; 3],
+    /// Length in angstrom.
+    pub length: f64,
+}
+
+impl Bond {
+    /// The bond order of this bond in `structure`, with the single-bond
+    /// radii of `table`, see `bond_order`.
+    pub fn order<T: LookupElement>(
+        &self,
+        table: &T,
+        structure: &Structure,
+    ) -> Result<BondOrderEstimate, BondError> {
+        bond_order(
+            table,
+            structure.atoms[self.i].symbol,
+            structure.atoms[self.j].symbol,
+            self.length,
+        )
+    }
+}
+
+impl Display for Bond {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.i + 1, self.j + 1)?;
+        if self.image != [0; 3] {
+            write!(
+                f,
+                " ({} {} {})",
+                self.image[0], self.image[1], self.image[2]
+            )?;
+        }
+        write!(f, ": {:.4} ang", self.length)
+    }
+}
+
+/// Finds bonds from the covalent radii of a table.
+#[derive(Debug)]
+pub struct BondDetector<'a, T: LookupElement> {
+    table: &'a T,
+    tolerance: f64,
+}
+
+impl<'a, T: LookupElement> BondDetector<'a, T> {
+    pub fn new(table: &'a T) -> Self {
+        Self {
+            table,
+            tolerance: 1.15,
+        }
+    }
+
+    /// Factor on the sum of covalent radii. Defaults to 1.15.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    fn radius(&self, symbol: ElementSymbol) -> Result<f64, BondError> {
+        self.table
+            .get_by_symbol(symbol)
+            .covalent_radius()
+            .ok_or(BondError::MissingRadius(symbol))
+    }
+
+    /// Pairs of atoms closer than `factor` times the sum of their covalent
+    /// radii, ordered by `i`, `j` and image.
+    fn pairs_within(&self, structure: &Structure, factor: f64) -> Result<Vec<Bond>, BondError> {
+        let radii = structure
+            .atoms
+            .iter()
+            .map(|atom| self.radius(atom.symbol))
+            .collect::<Result<Vec<f64>, _>>()?;
+        let max_cutoff = 2.0 * factor * radii.iter().cloned().fold(0.0, f64::max);
+        let (lattice, reciprocal) = match structure.lattice {
+            Some(lattice) => (Some(lattice), Some(lattice.reciprocal()?)),
+            None => (None, None),
+        };
+        // Images needed along each vector: the cutoff over the spacing of
+        // the lattice planes, plus one for the wrapped difference vector.
+        let range: [i32; 3] = match reciprocal {
+            Some(reciprocal) => {
+                reciprocal.map(|r| (max_cutoff * dot(r, r).sqrt()).ceil() as i32 + 1)
+            }
+            None => [0; 3],
+        };
+        let mut bonds = Vec::new();
+        for (i, a) in structure.atoms.iter().enumerate() {
+            for (j, b) in structure.atoms.iter().enumerate().skip(i) {
+                let cutoff = factor * (radii[i] + radii[j]);
+                let mut delta = [0.0; 3];
+                for (d, (x, y)) in delta.iter_mut().zip(b.position.iter().zip(a.position)) {
+                    *d = x - y;
+                }
+                // Shift `b` to the image nearest `a`.
+                let offset: [i32; 3] = match reciprocal {
+                    Some(reciprocal) => reciprocal.map(|r| -dot(r, delta).round() as i32),
+                    None => [0; 3],
+                };
+                for n0 in -range[0]..=range[0] {
+                    for n1 in -range[1]..=range[1] {
+                        for n2 in -range[2]..=range[2] {
+                            let image = [offset[0] + n0, offset[1] + n1, offset[2] + n2];
+                            // A bond to an image of the same atom is found
+                            // at `image` and `-image`; keep one.
+                            if i == j && image <= [0; 3] {
+                                continue;
+                            }
+                            let shift = lattice.map_or([0.0; 3], |lattice| {
+                                lattice.to_cartesian(image.map(f64::from))
+                            });
+                            let vector = [0, 1, 2].map(|k| delta[k] + shift[k]);
+                            let length = dot(vector, vector).sqrt();
+                            if length <= cutoff {
+                                bonds.push(Bond {
+                                    i,
+                                    j,
+                                    image,
+                                    length,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(bonds)
+    }
+
+    pub fn bonds(&self, structure: &Structure) -> Result<Vec<Bond>, BondError> {
+        self.pairs_within(structure, self.tolerance)
+    }
+
+    /// Bonds of each atom. A bond to an image of the atom itself counts
+    /// twice.
+    pub fn coordination_numbers(&self, structure: &Structure) -> Result<Vec<usize>, BondError> {
+        let mut counts = vec![0; structure.atoms.len()];
+        for bond in self.bonds(structure)? {
+            counts[bond.i] += 1;
+            counts[bond.j] += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Pairs closer than `factor` times the sum of their covalent radii,
+    /// e.g. 0.5, which usually means a broken structure.
+    pub fn close_contacts(
+        &self,
+        structure: &Structure,
+        factor: f64,
+    ) -> Result<Vec<Bond>, BondError> {
+        self.pairs_within(structure, factor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BondOrder {
+    Single,
+    Double,
+    Triple,
+}
+
+impl BondOrder {
+    pub fn order(&self) -> u8 {
+        match self {
+            BondOrder::Single => 1,
+            BondOrder::Double => 2,
+            BondOrder::Triple => 3,
+        }
+    }
+
+    /// The bond in a formula: `-`, `=` or `≡`.
+    pub fn symbol(&self) -> char {
+        match self {
+            BondOrder::Single => '-',
+            BondOrder::Double => '=',
+            BondOrder::Triple => '≡',
+        }
+    }
+}
+
+impl Display for BondOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BondOrder::Single => f.write_str("single"),
+            BondOrder::Double => f.write_str("double"),
+            BondOrder::Triple => f.write_str("triple"),
+        }
+    }
+}
+
+/// The bond order whose reference length is nearest a bond length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BondOrderEstimate {
+    pub atoms: [ElementSymbol; 2],
+    pub order: BondOrder,
+    /// Sum of the covalent radii of `order`, in angstrom.
+    pub reference_length: f64,
+    /// 1 at the reference length, falling to 0.5 halfway to the reference
+    /// length of the neighbouring order. Beyond the shortest or longest
+    /// reference it keeps falling, to 0 a whole gap away.
+    pub confidence: f64,
+}
+
+/// Written as e.g. `C=O: double, confidence 0.68`.
+impl Display for BondOrderEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}: {}, confidence {:.2}",
+            self.atoms[0],
+            self.order.symbol(),
+            self.atoms[1],
+            self.order,
+            self.confidence
+        )
+    }
+}
+
+/// Fraction of the reference length over which the confidence of a bond
+/// with a single reference, such as any bond to H, falls to 0.
+const SINGLE_REFERENCE_SPREAD: f64 = 0.1;
+
+/// Estimates the order of a bond of `distance` angstrom between `a` and
+/// `b`. The single-bond reference is the sum of the covalent radii of
+/// `table`, the others the Pyykko double- and triple-bond radii.
+///
+/// Only orders with radii for both elements are candidates, so H bonds are
+/// always single. This does not judge whether the atoms are bonded at all,
+/// which is the job of `BondDetector`, but lengths far outside the range of
+/// the references get a low confidence.
+/// # Example
+/// ```
+/// use castep_periodic_table::bonding::{bond_order, BondOrder};
+/// use castep_periodic_table::data::ELEMENT_TABLE;
+/// use castep_periodic_table::element::ElementSymbol;
+///
+/// // The C=O bond of formaldehyde, 1.205 ang, is nearer the double-bond
+/// // reference of 1.24 ang than the triple-bond one of 1.13 ang.
+/// let co = bond_order(&ELEMENT_TABLE, ElementSymbol::C, ElementSymbol::O, 1.205).unwrap();
+/// assert_eq!(co.order, BondOrder::Double);
+/// assert_eq!(co.to_string(), "C=O: double, confidence 0.68");
+/// ```
+pub fn bond_order<T: LookupElement>(
+    table: &T,
+    a: ElementSymbol,
+    b: ElementSymbol,
+    distance: f64,
+) -> Result<BondOrderEstimate, BondError> {
+    let single = |symbol: ElementSymbol| {
+        table
+            .get_by_symbol(symbol)
+            .covalent_radius()
+            .ok_or(BondError::MissingRadius(symbol))
+    };
+    let mut references = vec![(BondOrder::Single, single(a)? + single(b)?)];
+    for (order, kind) in [
+        (BondOrder::Double, RadiusKind::CovalentDouble),
+        (BondOrder::Triple, RadiusKind::CovalentTriple),
+    ] {
+        match (radius(a, kind), radius(b, kind)) {
+            (Some(ra), Some(rb)) => references.push((order, ra.angstrom() + rb.angstrom())),
+            _ => break,
+        }
+    }
+    let nearest = (0..references.len())
+        .min_by(|&x, &y| {
+            let dx = (distance - references[x].1).abs();
+            let dy = (distance - references[y].1).abs();
+            dx.total_cmp(&dy)
+        })
+        .expect("there is a single-bond reference");
+    let (order, reference_length) = references[nearest];
+    let lower = nearest.checked_sub(1);
+    let higher = Some(nearest + 1).filter(|&i| i < references.len());
+    // Longer bonds compete with the lower order, shorter ones with the
+    // higher order. Past the longest or shortest reference, the gap to the
+    // order on the other side sets the scale.
+    let neighbour = if distance > reference_length {
+        lower.or(higher)
+    } else {
+        higher.or(lower)
+    };
+    let gap = match neighbour {
+        Some(i) => (references[i].1 - reference_length).abs(),
+        None => SINGLE_REFERENCE_SPREAD * reference_length,
+    };
+    let confidence = (1.0 - (distance - reference_length).abs() / gap).max(0.0);
+    Ok(BondOrderEstimate {
+        atoms: [a, b],
+        order,
+        reference_length,
+        confidence,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BondError {
+    /// The lattice vectors are linearly dependent.
+    SingularLattice,
+    MissingRadius(ElementSymbol),
+}
+
+impl Display for BondError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BondError::SingularLattice => f.write_str("lattice vectors are linearly dependent"),
+            BondError::MissingRadius(symbol) => write!(f, "{symbol} has no covalent radius"),
+        }
+    }
+}
+
+impl std::error::Error for BondError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{ElementSymbol, ElementTable},
+        magnetism::Site,
+    };
+
+    use super::{bond_order, BondDetector, BondError, BondOrder, Lattice, Structure};
+
+    #[test]
+    fn test_molecule() {
+        // Water, with the hydrogens 1.0 ang apart.
+        let water = Structure::molecule([
+            (ElementSymbol::O, [0.0, 0.0, 0.0]),
+            (ElementSymbol::H, [0.757, 0.586, 0.0]),
+            (ElementSymbol::H, [-0.757, 0.586, 0.0]),
+        ]);
+        let detector = BondDetector::new(&ELEMENT_TABLE);
+        let bonds = detector.bonds(&water).unwrap();
+        let pairs: Vec<(usize, usize)> = bonds.iter().map(|b| (b.i, b.j)).collect();
+        assert_eq!(pairs, [(0, 1), (0, 2)]);
+        assert_eq!(bonds[0].to_string(), "1-2: 0.9573 ang");
+        assert_eq!(detector.coordination_numbers(&water).unwrap(), [2, 1, 1]);
+        assert!(detector.close_contacts(&water, 0.5).unwrap().is_empty());
+        assert_eq!(
+            detector
+                .tolerance(2.5)
+                .coordination_numbers(&water)
+                .unwrap(),
+            [2, 2, 2]
+        );
+    }
+
+    #[test]
+    fn test_periodic() {
+        // A chain of hydrogens 0.7 ang apart along a: each atom bonds to its
+        // own images on both sides.
+        let lattice = Lattice::new([[0.7, 0.0, 0.0], [0.0, 10.0, 0.0], [0.0, 0.0, 10.0]]);
+        let chain = Structure::from_fractional(lattice, [(ElementSymbol::H, [0.0; 3])]).unwrap();
+        let detector = BondDetector::new(&ELEMENT_TABLE);
+        let bonds = detector.bonds(&chain).unwrap();
+        assert_eq!(bonds.len(), 1);
+        assert_eq!(bonds[0].image, [1, 0, 0]);
+        assert_eq!(bonds[0].to_string(), "1-1 (1 0 0): 0.7000 ang");
+        assert_eq!(detector.coordination_numbers(&chain).unwrap(), [2]);
+
+        // Rock salt NaCl in the conventional cell, with atoms outside the
+        // cell: six neighbours each.
+        let a = 5.64;
+        let cubic = Lattice::new([[a, 0.0, 0.0], [0.0, a, 0.0], [0.0, 0.0, a]]);
+        let mut sites = Vec::new();
+        for shift in [
+            [0.0, 0.0, 0.0],
+            [0.5, 0.5, 0.0],
+            [0.5, 0.0, 0.5],
+            [0.0, 0.5, 0.5],
+        ] {
+            sites.push(Site::new(ElementSymbol::Na, shift));
+            sites.push(Site::new(
+                ElementSymbol::Cl,
+                [shift[0] + 0.5, shift[1] + 1.0, shift[2] - 2.0],
+            ));
+        }
+        let nacl = Structure::from_sites(cubic, &sites).unwrap();
+        // Na 1.55 + Cl 0.99 = 2.54, times 1.15 reaches the 2.82 bonds.
+        let counts = BondDetector::new(&ELEMENT_TABLE)
+            .coordination_numbers(&nacl)
+            .unwrap();
+        assert_eq!(counts, [6; 8]);
+
+        assert_eq!(
+            Structure::periodic(Lattice::new([[1.0, 0.0, 0.0]; 3]), []).unwrap_err(),
+            BondError::SingularLattice
+        );
+    }
+
+    #[test]
+    fn test_table_override() {
+        // Fe 1.16: bonded at 2.7 ang only with the tolerance raised.
+        let dimer = Structure::molecule([
+            (ElementSymbol::Fe, [0.0; 3]),
+            (ElementSymbol::Fe, [0.0, 0.0, 2.7]),
+        ]);
+        assert!(BondDetector::new(&ELEMENT_TABLE)
+            .bonds(&dimer)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            BondDetector::new(&ELEMENT_TABLE)
+                .tolerance(1.2)
+                .bonds(&dimer)
+                .unwrap()
+                .len(),
+            1
+        );
+        let table = ElementTable::builtin()
+            .with_override_str(
+                "test",
+                "Element_info:\n  - symbol: Fe\n    covalent_radius: 1.32\n",
+            )
+            .unwrap();
+        assert_eq!(
+            BondDetector::new(&table)
+                .coordination_numbers(&dimer)
+                .unwrap(),
+            [1, 1]
+        );
+        // Two atoms on top of each other.
+        let clash = Structure::molecule([
+            (ElementSymbol::Fe, [0.0; 3]),
+            (ElementSymbol::O, [0.0, 0.0, 0.3]),
+        ]);
+        let contacts = BondDetector::new(&ELEMENT_TABLE)
+            .close_contacts(&clash, 0.5)
+            .unwrap();
+        assert_eq!(contacts.len(), 1);
+    }
+
+    #[test]
+    fn test_bond_order() {
+        // HCN: C-H 1.064, C≡N 1.156.
+        let hcn = Structure::molecule([
+            (ElementSymbol::H, [0.0, 0.0, -1.064]),
+            (ElementSymbol::C, [0.0; 3]),
+            (ElementSymbol::N, [0.0, 0.0, 1.156]),
+        ]);
+        let bonds = BondDetector::new(&ELEMENT_TABLE).bonds(&hcn).unwrap();
+        let orders: Vec<String> = bonds
+            .iter()
+            .map(|bond| bond.order(&ELEMENT_TABLE, &hcn).unwrap().to_string())
+            .collect();
+        assert_eq!(
+            orders,
+            [
+                "H-C: single, confidence 0.94",
+                "C≡N: triple, confidence 0.88"
+            ]
+        );
+
+        // Carbon monoxide, ethylene and ethane.
+        let co = bond_order(&ELEMENT_TABLE, ElementSymbol::C, ElementSymbol::O, 1.128).unwrap();
+        assert_eq!(co.order, BondOrder::Triple);
+        assert!((co.reference_length - 1.13).abs() < 1e-12);
+        let cc = |d| bond_order(&ELEMENT_TABLE, ElementSymbol::C, ElementSymbol::C, d).unwrap();
+        assert_eq!(cc(1.339).order, BondOrder::Double);
+        assert_eq!(cc(1.535).order, BondOrder::Single);
+        assert_eq!(cc(1.50).confidence, 1.0);
+        // Halfway between the single and double references.
+        let midway = cc(1.42);
+        assert!((midway.confidence - 0.5).abs() < 1e-9);
+        assert!(cc(1.40).confidence > cc(1.41).confidence);
+        // Beyond the single-bond reference the confidence keeps falling.
+        assert!((cc(1.58).confidence - 0.5).abs() < 1e-9);
+        assert_eq!(cc(3.0).to_string(), "C-C: single, confidence 0.00");
+        assert_eq!(BondOrder::Double.order(), 2);
+
+        // The single-bond reference follows the table.
+        let table = ElementTable::builtin()
+            .with_override_str(
+                "test",
+                "Element_info:\n  - symbol: C\n    covalent_radius: 0.8\n",
+            )
+            .unwrap();
+        let cc = bond_order(&table, ElementSymbol::C, ElementSymbol::C, 1.6).unwrap();
+        assert_eq!((cc.order, cc.confidence), (BondOrder::Single, 1.0));
+    }
+}
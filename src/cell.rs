@@ -0,0 +1,333 @@
+//! Writers for the species-related blocks of a CASTEP `.cell` file.
+//!
+//! ```
+//! use castep_periodic_table::cell::{MassUnit, SpeciesBlocks};
+//! use castep_periodic_table::element::ElementSymbol;
+//!
+//! let blocks = SpeciesBlocks::new([ElementSymbol::O, ElementSymbol::C, ElementSymbol::O])
+//!     .mass_unit(MassUnit::Amu)
+//!     .mass_precision(4);
+//! assert_eq!(
+//!     blocks.species_mass(),
+//!     "%BLOCK SPECIES_MASS\namu\n       C           12.0110\n       O           15.9990\n%ENDBLOCK SPECIES_MASS\n"
+//! );
+//! ```
+
+use std::{borrow::Borrow, collections::BTreeSet, fmt::Display};
+
+use crate::{
+    data::ELEMENT_TABLE,
+    element::{Element, ElementSymbol, LcaoError, LookupElement, Species},
+};
+
+/// Mass units accepted in the `SPECIES_MASS` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MassUnit {
+    Amu,
+    /// Electron mass
+    Me,
+    Kg,
+    G,
+}
+
+impl MassUnit {
+    /// Convert a mass in amu, as stored in the table, to this unit.
+    pub fn convert_amu(&self, mass: f64) -> f64 {
+        match self {
+            MassUnit::Amu => mass,
+            MassUnit::Me => mass * 1822.888486209,
+            MassUnit::Kg => mass * 1.66053906660e-27,
+            MassUnit::G => mass * 1.66053906660e-24,
+        }
+    }
+}
+
+impl Display for MassUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = match self {
+            MassUnit::Amu => "amu",
+            MassUnit::Me => "me",
+            MassUnit::Kg => "kg",
+            MassUnit::G => "g",
+        };
+        f.write_str(unit)
+    }
+}
+
+/// Builds the `SPECIES_POT`, `SPECIES_MASS` and `SPECIES_LCAO_STATES` blocks
+/// for a set of species. Species are deduplicated and ordered by atomic
+/// number, then label. `blocks` writes all three. `Display` also writes
+/// all three, but does not check the LCAO states.
+#[derive(Debug, Clone)]
+pub struct SpeciesBlocks<'a, T: LookupElement> {
+    table: &'a T,
+    species: Vec<Species>,
+    mass_unit: Option<MassUnit>,
+    mass_precision: usize,
+}
+
+impl SpeciesBlocks<'static, [Element; 103]> {
+    /// Use the values in `ELEMENT_TABLE`.
+    pub fn new<I>(symbols: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<ElementSymbol>,
+    {
+        Self::with_table(&ELEMENT_TABLE, symbols)
+    }
+}
+
+impl<'a, T: LookupElement> SpeciesBlocks<'a, T> {
+    pub fn with_table<I>(table: &'a T, symbols: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<ElementSymbol>,
+    {
+        Self::with_species(
+            table,
+            symbols
+                .into_iter()
+                .map(|symbol| Species::from(*symbol.borrow())),
+        )
+    }
+
+    /// Blocks for species with their own labels and values, e.g. `Fe1` and
+    /// `Fe2`. Of species with the same label, the first one is kept.
+    pub fn with_species<I>(table: &'a T, species: I) -> Self
+    where
+        I: IntoIterator<Item = Species>,
+    {
+        let mut unique = BTreeSet::new();
+        for item in species {
+            // `insert` keeps the existing entry.
+            unique.insert(item);
+        }
+        Self {
+            table,
+            species: unique.into_iter().collect(),
+            mass_unit: None,
+            mass_precision: 10,
+        }
+    }
+
+    /// Write a unit line in `SPECIES_MASS`. Without it CASTEP assumes amu.
+    pub fn mass_unit(mut self, unit: MassUnit) -> Self {
+        self.mass_unit = Some(unit);
+        self
+    }
+
+    /// Number of decimals for masses. Defaults to 10, the precision of the
+    /// stored values.
+    pub fn mass_precision(mut self, precision: usize) -> Self {
+        self.mass_precision = precision;
+        self
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// Each species with its element values resolved.
+    fn elements(&self) -> impl Iterator<Item = (&Species, Element)> {
+        self.species
+            .iter()
+            .map(|species| (species, species.resolve(self.table)))
+    }
+
+    pub fn species_pot(&self) -> String {
+        let lines: String = self
+            .elements()
+            .map(|(species, elm)| format!("{:>8}  {}\n", species, elm.potential()))
+            .collect();
+        format!("%BLOCK SPECIES_POT\n{lines}%ENDBLOCK SPECIES_POT\n")
+    }
+
+    pub fn species_mass(&self) -> String {
+        let unit = self.mass_unit.unwrap_or(MassUnit::Amu);
+        let lines: String = self
+            .elements()
+            .map(|(species, elm)| {
+                let mass = unit.convert_amu(elm.mass());
+                match unit {
+                    MassUnit::Kg | MassUnit::G => {
+                        format!("{:>8} {:>17.*e}\n", species, self.mass_precision, mass)
+                    }
+                    _ => format!("{:>8} {:>17.*}\n", species, self.mass_precision, mass),
+                }
+            })
+            .collect();
+        let header = self
+            .mass_unit
+            .map_or(String::new(), |unit| format!("{unit}\n"));
+        format!("%BLOCK SPECIES_MASS\n{header}{lines}%ENDBLOCK SPECIES_MASS\n")
+    }
+
+    /// Fails when the LCAO states of a species miss one of its valence
+    /// shells, or its valence charge does not fill whole shells, as
+    /// Mulliken analysis would be meaningless. Species whose valence charge
+    /// is not known are written unchecked, and species without a number of
+    /// states are left for CASTEP to choose.
+    pub fn species_lcao_states(&self) -> Result<String, LcaoError> {
+        let mut lines = String::new();
+        for species in self.species.iter() {
+            let Some(lcao) = species.lcao(self.table) else {
+                continue;
+            };
+            let total = match species.valence_charge(self.table) {
+                Some(_) => species.lcao_states(self.table)?.total(),
+                None => lcao,
+            };
+            lines.push_str(&format!("{:>8} {:>9}\n", species, total));
+        }
+        Ok(format!(
+            "%BLOCK SPECIES_LCAO_STATES\n{lines}%ENDBLOCK SPECIES_LCAO_STATES\n"
+        ))
+    }
+
+    /// The `SPECIES_LCAO_STATES` block as given, without checks.
+    fn unchecked_lcao_states(&self) -> String {
+        let lines: String = self
+            .species
+            .iter()
+            .filter_map(|species| {
+                species
+                    .lcao(self.table)
+                    .map(|lcao| format!("{:>8} {:>9}\n", species, lcao))
+            })
+            .collect();
+        format!("%BLOCK SPECIES_LCAO_STATES\n{lines}%ENDBLOCK SPECIES_LCAO_STATES\n")
+    }
+
+    /// All three blocks.
+    pub fn blocks(&self) -> Result<String, LcaoError> {
+        Ok(format!(
+            "{}\n{}\n{}",
+            self.species_mass(),
+            self.species_pot(),
+            self.species_lcao_states()?
+        ))
+    }
+}
+
+/// All three blocks, with the LCAO states unchecked. Use `blocks` to have
+/// them checked.
+impl<T: LookupElement> Display for SpeciesBlocks<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\n{}\n{}",
+            self.species_mass(),
+            self.species_pot(),
+            self.unchecked_lcao_states()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{AngularMomentum, ElementSymbol, LcaoError, LcaoStates, Species},
+    };
+
+    use super::{MassUnit, SpeciesBlocks};
+
+    #[test]
+    fn test_species_blocks() {
+        let structure = [
+            ElementSymbol::Fe,
+            ElementSymbol::O,
+            ElementSymbol::Fe,
+            ElementSymbol::O,
+            ElementSymbol::O,
+        ];
+        let blocks = SpeciesBlocks::new(structure.iter());
+        let labels: Vec<&str> = blocks.species().iter().map(|s| s.label()).collect();
+        assert_eq!(labels, ["O", "Fe"]);
+        assert_eq!(
+            blocks.species_pot(),
+            "%BLOCK SPECIES_POT\n       O  O_00PBE.usp\n      Fe  Fe_00PBE.uspcc\n%ENDBLOCK SPECIES_POT\n"
+        );
+        assert_eq!(
+            blocks.species_lcao_states().unwrap(),
+            "%BLOCK SPECIES_LCAO_STATES\n       O         2\n      Fe         3\n%ENDBLOCK SPECIES_LCAO_STATES\n"
+        );
+        assert_eq!(
+            blocks.species_mass(),
+            "%BLOCK SPECIES_MASS\n       O     15.9989995956\n      Fe     55.8470001221\n%ENDBLOCK SPECIES_MASS\n"
+        );
+        let kg = SpeciesBlocks::new([ElementSymbol::H])
+            .mass_unit(MassUnit::Kg)
+            .mass_precision(4);
+        assert_eq!(
+            kg.species_mass(),
+            "%BLOCK SPECIES_MASS\nkg\n       H        1.6738e-27\n%ENDBLOCK SPECIES_MASS\n"
+        );
+        assert_eq!(blocks.to_string(), blocks.blocks().unwrap());
+    }
+
+    #[test]
+    fn test_species_labels() {
+        let species = [
+            "Fe2".parse::<Species>().unwrap(),
+            "Fe1".parse::<Species>().unwrap().with_lcao(4),
+            "O".parse::<Species>().unwrap(),
+            "Fe1".parse::<Species>().unwrap(),
+            "C:exc"
+                .parse::<Species>()
+                .unwrap()
+                .with_potential("C_00PBE_OTF.usp".parse().unwrap()),
+        ];
+        let blocks = SpeciesBlocks::with_species(&ELEMENT_TABLE, species);
+        assert_eq!(
+            blocks.species_pot(),
+            "%BLOCK SPECIES_POT\n   C:exc  C_00PBE_OTF.usp\n       O  O_00PBE.usp\n     Fe1  Fe_00PBE.uspcc\n     Fe2  Fe_00PBE.uspcc\n%ENDBLOCK SPECIES_POT\n"
+        );
+        assert_eq!(
+            blocks.species_lcao_states().unwrap(),
+            "%BLOCK SPECIES_LCAO_STATES\n   C:exc         2\n       O         2\n     Fe1         4\n     Fe2         3\n%ENDBLOCK SPECIES_LCAO_STATES\n"
+        );
+    }
+
+    #[test]
+    fn test_lcao_validation() {
+        let fe = Species::from(ElementSymbol::Fe).with_lcao_states(LcaoStates::new(1, 1, 2, 0));
+        let blocks = SpeciesBlocks::with_species(&ELEMENT_TABLE, [fe]);
+        assert!(blocks
+            .species_lcao_states()
+            .unwrap()
+            .contains("      Fe         4\n"));
+        let no_d = "Fe1"
+            .parse::<Species>()
+            .unwrap()
+            .with_lcao_states(LcaoStates::new(1, 2, 0, 0));
+        let error = SpeciesBlocks::with_species(&ELEMENT_TABLE, [no_d])
+            .blocks()
+            .unwrap_err();
+        let LcaoError::Species { label, error } = error else {
+            panic!("{error}");
+        };
+        assert_eq!(label, "Fe1");
+        assert!(matches!(
+            *error,
+            LcaoError::MissingChannel {
+                l: AngularMomentum::D,
+                ..
+            }
+        ));
+        let too_few = "Fe2".parse::<Species>().unwrap().with_lcao(1);
+        let blocks = SpeciesBlocks::with_species(&ELEMENT_TABLE, [too_few]);
+        assert!(blocks.species_lcao_states().is_err());
+        // `Display` writes the states unchecked.
+        assert!(blocks.to_string().contains("     Fe2         1\n"));
+        // 7 electrons split the 4s shell of `[Ar] 3d6 4s2`.
+        let odd = "Fe3".parse::<Species>().unwrap().with_valence_charge(7);
+        assert_eq!(
+            SpeciesBlocks::with_species(&ELEMENT_TABLE, [odd])
+                .species_lcao_states()
+                .unwrap_err()
+                .to_string(),
+            "species Fe3: 7 valence electrons do not fill the outer shells of [Ar] 3d6 4s2"
+        );
+    }
+}
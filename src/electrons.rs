@@ -0,0 +1,193 @@
+//! Valence electron counting and `SPIN` consistency checks, so that
+//! incompatible charge and spin settings are caught before a job is queued.
+//!
+//! ```
+//! use castep_periodic_table::data::ELEMENT_TABLE;
+//! use castep_periodic_table::electrons::ElectronCount;
+//! use castep_periodic_table::formula::Composition;
+//!
+//! let fe2o3: Composition = "Fe4O6".parse().unwrap();
+//! let count = ElectronCount::new(&fe2o3, 0, &ELEMENT_TABLE).unwrap();
+//! assert_eq!(count.electrons(), 4 * 8 + 6 * 6);
+//! assert_eq!(count.default_spin(), 16);
+//! assert!(count.check_spin(16).is_ok());
+//! assert!(count.check_spin(15).is_err());
+//! ```
+
+use std::fmt::Display;
+
+use crate::{
+    element::{ElementSymbol, LookupElement},
+    formula::Composition,
+};
+
+/// Electrons of a cell from the valence charges of its potentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElectronCount {
+    electrons: u32,
+    default_spin: u32,
+}
+
+impl ElectronCount {
+    /// Count the electrons of `composition`, the number of atoms of each
+    /// species in the cell, with a net `charge` in units of e, as in the
+    /// CASTEP `CHARGE` parameter.
+    pub fn new<T: LookupElement>(
+        composition: &Composition,
+        charge: i32,
+        table: &T,
+    ) -> Result<Self, ElectronError> {
+        let mut electrons: i64 = 0;
+        let mut default_spin: u32 = 0;
+        for (symbol, amount) in composition.iter() {
+            if (amount - amount.round()).abs() > 1e-9 || amount < 0.0 {
+                return Err(ElectronError::FractionalAmount(symbol, amount));
+            }
+            let atoms = amount.round() as i64;
+            let element = table.get_by_symbol(symbol);
+            let valence_charge = element
+                .valence_charge()
+                .ok_or(ElectronError::UnknownValenceCharge(symbol))?;
+            electrons += atoms * valence_charge as i64;
+            default_spin += atoms as u32 * element.spin() as u32;
+        }
+        let electrons = electrons - charge as i64;
+        if electrons < 0 {
+            return Err(ElectronError::TooFewElectrons(electrons));
+        }
+        Ok(Self {
+            electrons: electrons as u32,
+            default_spin,
+        })
+    }
+
+    /// Total number of valence electrons, after removing the net charge.
+    pub fn electrons(&self) -> u32 {
+        self.electrons
+    }
+
+    /// Sum of `Element::spin()` over all atoms, i.e. every moment aligned.
+    pub fn default_spin(&self) -> u32 {
+        self.default_spin
+    }
+
+    /// Check a `SPIN` value, the number of unpaired electrons, against the
+    /// electron count.
+    pub fn check_spin(&self, spin: u32) -> Result<(), ElectronError> {
+        if spin > self.electrons {
+            Err(ElectronError::SpinExceedsElectrons {
+                electrons: self.electrons,
+                spin,
+            })
+        } else if !(self.electrons - spin).is_multiple_of(2) {
+            Err(ElectronError::SpinParity {
+                electrons: self.electrons,
+                spin,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The default spin, lowered by one when its parity disagrees with the
+    /// electron count, and clamped to the number of electrons.
+    pub fn proposed_spin(&self) -> u32 {
+        let spin = self.default_spin.min(self.electrons);
+        if (self.electrons - spin).is_multiple_of(2) {
+            spin
+        } else if spin > 0 {
+            spin - 1
+        } else {
+            1
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElectronError {
+    /// Species counts must be whole atoms.
+    FractionalAmount(ElementSymbol, f64),
+    /// The potential of the element has no valence charge in the table.
+    UnknownValenceCharge(ElementSymbol),
+    /// The charge removes more electrons than there are.
+    TooFewElectrons(i64),
+    /// Up and down electrons cannot be split into whole numbers.
+    SpinParity {
+        electrons: u32,
+        spin: u32,
+    },
+    SpinExceedsElectrons {
+        electrons: u32,
+        spin: u32,
+    },
+}
+
+impl Display for ElectronError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ElectronError::FractionalAmount(symbol, amount) => {
+                write!(f, "{symbol} has a fractional number of atoms: {amount}")
+            }
+            ElectronError::UnknownValenceCharge(symbol) => {
+                write!(
+                    f,
+                    "the valence charge of the {symbol} potential is not known"
+                )
+            }
+            ElectronError::TooFewElectrons(electrons) => {
+                write!(f, "charge leaves {electrons} electrons")
+            }
+            ElectronError::SpinParity { electrons, spin } => write!(
+                f,
+                "SPIN {spin} is incompatible with {electrons} electrons: their parities differ"
+            ),
+            ElectronError::SpinExceedsElectrons { electrons, spin } => {
+                write!(f, "SPIN {spin} exceeds the {electrons} electrons")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ElectronError {}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{data::ELEMENT_TABLE, element::ElementSymbol, formula::Composition};
+
+    use super::{ElectronCount, ElectronError};
+
+    #[test]
+    fn test_electron_count() {
+        let water = Composition::from_str("H2O").unwrap();
+        let count = ElectronCount::new(&water, 0, &ELEMENT_TABLE).unwrap();
+        assert_eq!(count.electrons(), 8);
+        assert_eq!(count.default_spin(), 0);
+        assert_eq!(count.proposed_spin(), 0);
+        let water_cation = ElectronCount::new(&water, 1, &ELEMENT_TABLE).unwrap();
+        assert_eq!(water_cation.electrons(), 7);
+        assert!(matches!(
+            water_cation.check_spin(0),
+            Err(ElectronError::SpinParity {
+                electrons: 7,
+                spin: 0
+            })
+        ));
+        assert_eq!(water_cation.proposed_spin(), 1);
+        let nio: Composition = [ElementSymbol::Ni, ElementSymbol::O].into_iter().collect();
+        let count = ElectronCount::new(&nio, 1, &ELEMENT_TABLE).unwrap();
+        assert_eq!(count.electrons(), 15);
+        assert_eq!(count.default_spin(), 2);
+        assert_eq!(count.proposed_spin(), 1);
+        assert!(count.check_spin(16).is_err());
+        let lsmo = Composition::from_str("La0.7Sr0.3MnO3").unwrap();
+        assert!(ElectronCount::new(&lsmo, 0, &ELEMENT_TABLE).is_err());
+        assert!(ElectronCount::new(&water, 9, &ELEMENT_TABLE).is_err());
+        let ceria = Composition::from_str("CeO2").unwrap();
+        assert!(matches!(
+            ElectronCount::new(&ceria, 0, &ELEMENT_TABLE),
+            Err(ElectronError::UnknownValenceCharge(ElementSymbol::Ce))
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+//! Code generation from `element_table.yaml`.
+//!
+//! This file is shared by `build.rs`, through `#[path]`, and by
+//! `ElementYamlTable::export_struct`, so it must only depend on `std` and
+//! `serde`.
+#![allow(dead_code)]
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawElementTable {
+    #[serde(rename = "Element_info")]
+    pub elements: Vec<RawElement>,
+}
+
+/// An entry of `element_table.yaml`, with the symbol kept as text.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawElement {
+    pub symbol: String,
+    pub atomic_number: u8,
+    #[serde(rename = "LCAO")]
+    pub lcao: u8,
+    pub mass: f64,
+    pub potential: String,
+    pub valence_charge: Option<u8>,
+    pub cutoffs: Option<RawCutoffs>,
+    pub spin: u8,
+    pub covalent_radius: Option<f64>,
+}
+
+/// Recommended cutoff energies of the potential, in eV.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RawCutoffs {
+    pub coarse: f64,
+    pub medium: f64,
+    pub fine: f64,
+}
+
+const POTENTIAL_FORMATS: [(&str, &str); 4] = [
+    ("usp", "Usp"),
+    ("uspcc", "Uspcc"),
+    ("recpot", "Recpot"),
+    ("ncp", "Ncp"),
+];
+
+/// Rust expression of the `Option<Cutoffs>` of an element.
+fn cutoffs_expr(cutoffs: Option<RawCutoffs>) -> String {
+    match cutoffs {
+        Some(RawCutoffs {
+            coarse,
+            medium,
+            fine,
+        }) => format!("Some(Cutoffs {{ coarse: {coarse:?}, medium: {medium:?}, fine: {fine:?} }})"),
+        None => "None".to_string(),
+    }
+}
+
+/// Rust expression of a file-based `Potential`.
+pub fn potential_expr(potential: &str) -> Result<String, String> {
+    let format = potential
+        .rsplit_once('.')
+        .and_then(|(_stem, ext)| {
+            POTENTIAL_FORMATS
+                .iter()
+                .find(|(known, _variant)| known.eq_ignore_ascii_case(ext))
+        })
+        .map(|(_ext, variant)| variant)
+        .ok_or_else(|| format!("`{potential}` is not a pseudopotential file name"))?;
+    Ok(format!(
+        "Potential::File(PotentialFile {{ name: Cow::Borrowed({potential:?}), format: PotentialFormat::{format} }})"
+    ))
+}
+
+/// Checks that the table is ordered by atomic number and has no duplicated
+/// symbols or atomic numbers.
+pub fn validate(elements: &[RawElement]) -> Result<(), String> {
+    for pair in elements.windows(2) {
+        if pair[0].atomic_number >= pair[1].atomic_number {
+            return Err(format!(
+                "{} (atomic number {}) is listed before {} (atomic number {})",
+                pair[0].symbol, pair[0].atomic_number, pair[1].symbol, pair[1].atomic_number
+            ));
+        }
+    }
+    for (i, elm) in elements.iter().enumerate() {
+        if elements[..i].iter().any(|other| other.symbol == elm.symbol) {
+            return Err(format!("symbol {} is listed twice", elm.symbol));
+        }
+    }
+    Ok(())
+}
+
+/// The `ELEMENT_TABLE` constant.
+pub fn element_table(elements: &[RawElement]) -> Result<String, String> {
+    let entries = elements
+        .iter()
+        .map(|elm| {
+            // Debug formatter is used for floats to avoid making f64 numbers like `147.0` to `147`
+            Ok(format!(
+                "Element{{ symbol: ElementSymbol::{}, atomic_number: {}_u8, lcao: Some({}_u8), mass: {:?}, potential: {}, valence_charge: {:?}, cutoffs: {}, spin:{}_u8, covalent_radius: {:?}\n}}",
+                elm.symbol,
+                elm.atomic_number,
+                elm.lcao,
+                elm.mass,
+                potential_expr(&elm.potential)?,
+                elm.valence_charge,
+                cutoffs_expr(elm.cutoffs),
+                elm.spin,
+                elm.covalent_radius
+            ))
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+    Ok(format!(
+        "pub const ELEMENT_TABLE: [Element; {}] = [{}];",
+        elements.len(),
+        entries.join(", ")
+    ))
+}
+
+/// Lookup functions from symbols and atomic numbers to `ElementSymbol`, and
+/// compile-time checks that the enum agrees with the table.
+pub fn symbol_lookup(elements: &[RawElement]) -> String {
+    let symbol_arms: String = elements
+        .iter()
+        .map(|elm| {
+            format!(
+                "        {:?} => Some(ElementSymbol::{}),\n",
+                elm.symbol, elm.symbol
+            )
+        })
+        .collect();
+    let number_str_arms: String = elements
+        .iter()
+        .map(|elm| {
+            format!(
+                "        \"{}\" => Some(ElementSymbol::{}),\n",
+                elm.atomic_number, elm.symbol
+            )
+        })
+        .collect();
+    let number_arms: String = elements
+        .iter()
+        .map(|elm| {
+            format!(
+                "        {} => Some(ElementSymbol::{}),\n",
+                elm.atomic_number, elm.symbol
+            )
+        })
+        .collect();
+    let assertions: String = elements
+        .iter()
+        .map(|elm| {
+            format!(
+                "    assert!(ElementSymbol::{0} as u8 == {1}, \"ElementSymbol::{0} disagrees with element_table.yaml\");\n",
+                elm.symbol, elm.atomic_number
+            )
+        })
+        .collect();
+    let variants: Vec<String> = elements
+        .iter()
+        .map(|elm| format!("ElementSymbol::{}", elm.symbol))
+        .collect();
+    format!(
+        "/// Symbol or atomic number as text.
+fn symbol_from_str(s: &str) -> Option<ElementSymbol> {{
+    match s {{
+{symbol_arms}{number_str_arms}        _ => None,
+    }}
+}}
+
+fn symbol_from_u64(n: u64) -> Option<ElementSymbol> {{
+    match n {{
+{number_arms}        _ => None,
+    }}
+}}
+
+const _: () = {{
+{assertions}}};
+
+/// Fails to compile if the enum has a variant missing from the table.
+#[allow(dead_code)]
+const fn every_variant_is_in_table(symbol: ElementSymbol) {{
+    match symbol {{
+        {} => (),
+    }}
+}}
+",
+        variants.join(" | ")
+    )
+}
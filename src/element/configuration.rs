@@ -0,0 +1,454 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{AngularMomentum, ElementSymbol, LookupElement};
+
+/// An occupied shell such as `3d6`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shell {
+    pub n: u8,
+    pub l: AngularMomentum,
+    pub electrons: u8,
+}
+
+impl Shell {
+    pub fn new(n: u8, l: AngularMomentum, electrons: u8) -> Self {
+        Self { n, l, electrons }
+    }
+
+    /// Electrons in a full shell, `2(2l + 1)`.
+    pub fn capacity(&self) -> u8 {
+        2 * (2 * self.l.l() + 1)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.electrons == self.capacity()
+    }
+}
+
+impl Display for Shell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.n, self.l.letter(), self.electrons)
+    }
+}
+
+impl FromStr for Shell {
+    type Err = ConfigurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConfigurationError::InvalidShell(s.to_string());
+        let letter_at = s
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(invalid)?;
+        let n: u8 = s[..letter_at].parse().map_err(|_| invalid())?;
+        let mut rest = s[letter_at..].chars();
+        let l = rest
+            .next()
+            .and_then(AngularMomentum::from_letter)
+            .ok_or_else(invalid)?;
+        let electrons = rest.as_str();
+        if !electrons.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let electrons: u8 = electrons.parse().map_err(|_| invalid())?;
+        let shell = Self { n, l, electrons };
+        if l.l() >= n || electrons > shell.capacity() {
+            return Err(invalid());
+        }
+        Ok(shell)
+    }
+}
+
+/// Occupations `(n, l, electrons)` replacing those of the Madelung order.
+type ShellChanges = &'static [(u8, AngularMomentum, u8)];
+
+/// Ground-state configurations that differ from the Madelung order.
+const AUFBAU_EXCEPTIONS: &[(ElementSymbol, ShellChanges)] = {
+    use AngularMomentum::{D, F, P, S};
+    use ElementSymbol::*;
+    &[
+        (Cr, &[(3, D, 5), (4, S, 1)]),
+        (Cu, &[(3, D, 10), (4, S, 1)]),
+        (Nb, &[(4, D, 4), (5, S, 1)]),
+        (Mo, &[(4, D, 5), (5, S, 1)]),
+        (Ru, &[(4, D, 7), (5, S, 1)]),
+        (Rh, &[(4, D, 8), (5, S, 1)]),
+        (Pd, &[(4, D, 10), (5, S, 0)]),
+        (Ag, &[(4, D, 10), (5, S, 1)]),
+        (La, &[(4, F, 0), (5, D, 1)]),
+        (Ce, &[(4, F, 1), (5, D, 1)]),
+        (Gd, &[(4, F, 7), (5, D, 1)]),
+        (Pt, &[(5, D, 9), (6, S, 1)]),
+        (Au, &[(5, D, 10), (6, S, 1)]),
+        (Ac, &[(5, F, 0), (6, D, 1)]),
+        (Th, &[(5, F, 0), (6, D, 2)]),
+        (Pa, &[(5, F, 2), (6, D, 1)]),
+        (U, &[(5, F, 3), (6, D, 1)]),
+        (Np, &[(5, F, 4), (6, D, 1)]),
+        (Cm, &[(5, F, 7), (6, D, 1)]),
+        (Lr, &[(6, D, 0), (7, P, 1)]),
+    ]
+};
+
+/// Shells `(n, l)` up to 8s in filling order: by `n + l`, then `n`.
+pub(super) fn madelung_order() -> impl Iterator<Item = (u8, AngularMomentum)> {
+    (1..=8u8).flat_map(|sum| {
+        (0..sum.min(4)).rev().filter_map(move |l| {
+            let n = sum - l;
+            (l < n).then(|| (n, AngularMomentum::from_l(l).expect("l is below 4")))
+        })
+    })
+}
+
+const NOBLE_GASES: [ElementSymbol; 6] = [
+    ElementSymbol::He,
+    ElementSymbol::Ne,
+    ElementSymbol::Ar,
+    ElementSymbol::Kr,
+    ElementSymbol::Xe,
+    ElementSymbol::Rn,
+];
+
+/// Occupied shells of an atom or ion, ordered by `n`, then `l`.
+///
+/// Written with a noble-gas core where possible, e.g. `[Ar] 3d6 4s2`.
+/// # Example
+/// ```
+/// use castep_periodic_table::data::ELEMENT_TABLE;
+/// use castep_periodic_table::element::{ElectronConfiguration, ElementSymbol};
+///
+/// let cu = ElectronConfiguration::ground_state(ElementSymbol::Cu);
+/// assert_eq!(cu.to_string(), "[Ar] 3d10 4s1");
+///
+/// // `Fe_00PBE.uspcc` has 8 valence electrons: 3d6 4s2.
+/// let valence = ElectronConfiguration::valence_of(&ELEMENT_TABLE, ElementSymbol::Fe).unwrap();
+/// assert_eq!(valence.to_string(), "3d6 4s2");
+/// assert_eq!(valence.channel_shells(), [1, 0, 1, 0]);
+///
+/// let parsed: ElectronConfiguration = "[Ar]3d6 4s2".parse().unwrap();
+/// assert_eq!(parsed, ElectronConfiguration::ground_state(ElementSymbol::Fe));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ElectronConfiguration {
+    shells: Vec<Shell>,
+}
+
+impl ElectronConfiguration {
+    /// Shells in any order. Empty shells are dropped.
+    pub fn new<I>(shells: I) -> Result<Self, ConfigurationError>
+    where
+        I: IntoIterator<Item = Shell>,
+    {
+        let mut configuration = Self::default();
+        for shell in shells {
+            if shell.l.l() >= shell.n || shell.electrons > shell.capacity() {
+                return Err(ConfigurationError::InvalidShell(shell.to_string()));
+            }
+            if configuration.occupation(shell.n, shell.l) > 0 {
+                return Err(ConfigurationError::DuplicateShell(shell.n, shell.l));
+            }
+            configuration.set(shell.n, shell.l, shell.electrons);
+        }
+        Ok(configuration)
+    }
+
+    /// The neutral-atom ground state: the Madelung order with the known
+    /// exceptions, e.g. Cr `[Ar] 3d5 4s1` and Pd `[Kr] 4d10`.
+    pub fn ground_state(symbol: ElementSymbol) -> Self {
+        let mut configuration = Self::default();
+        let mut remaining = symbol as u8;
+        for (n, l) in madelung_order() {
+            let electrons = remaining.min(Shell::new(n, l, 0).capacity());
+            configuration.set(n, l, electrons);
+            remaining -= electrons;
+            if remaining == 0 {
+                break;
+            }
+        }
+        if let Some((_, changes)) = AUFBAU_EXCEPTIONS.iter().find(|(s, _)| *s == symbol) {
+            for &(n, l, electrons) in changes.iter() {
+                configuration.set(n, l, electrons);
+            }
+        }
+        configuration
+    }
+
+    /// The valence shells of `symbol` for the valence charge of its
+    /// potential in `table`.
+    pub fn valence_of<T: LookupElement>(
+        table: &T,
+        symbol: ElementSymbol,
+    ) -> Result<Self, ConfigurationError> {
+        let valence_charge = table
+            .get_by_symbol(symbol)
+            .valence_charge()
+            .ok_or(ConfigurationError::UnknownValenceCharge(symbol))?;
+        Self::ground_state(symbol).valence(valence_charge)
+    }
+
+    /// The outermost shells, counted down from the highest `n` and `l`,
+    /// that hold `valence_charge` electrons, e.g. `5s2 5p6 5d1 6s2` for Lu
+    /// with 11. An error if the charge does not end on a shell boundary.
+    pub fn valence(&self, valence_charge: u8) -> Result<Self, ConfigurationError> {
+        let mut counted = 0;
+        let mut start = self.shells.len();
+        while counted < valence_charge && start > 0 {
+            start -= 1;
+            counted += self.shells[start].electrons;
+        }
+        if counted != valence_charge {
+            return Err(ConfigurationError::ValenceMismatch {
+                configuration: self.to_string(),
+                valence_charge,
+            });
+        }
+        Ok(Self {
+            shells: self.shells[start..].to_vec(),
+        })
+    }
+
+    pub fn shells(&self) -> &[Shell] {
+        &self.shells
+    }
+
+    pub fn electrons(&self) -> u8 {
+        self.shells.iter().map(|shell| shell.electrons).sum()
+    }
+
+    pub fn occupation(&self, n: u8, l: AngularMomentum) -> u8 {
+        self.shells
+            .iter()
+            .find(|shell| shell.n == n && shell.l == l)
+            .map_or(0, |shell| shell.electrons)
+    }
+
+    /// Number of occupied shells of each angular momentum, indexed by `l`.
+    pub fn channel_shells(&self) -> [u8; 4] {
+        let mut counts = [0; 4];
+        for shell in self.shells.iter() {
+            counts[shell.l.l() as usize] += 1;
+        }
+        counts
+    }
+
+    /// Electrons of each angular momentum, indexed by `l`.
+    pub fn channel_electrons(&self) -> [u8; 4] {
+        let mut counts = [0; 4];
+        for shell in self.shells.iter() {
+            counts[shell.l.l() as usize] += shell.electrons;
+        }
+        counts
+    }
+
+    /// All shells written out, e.g. `1s2 2s2 2p6 3s1`.
+    pub fn full_notation(&self) -> String {
+        let shells: Vec<String> = self.shells.iter().map(|shell| shell.to_string()).collect();
+        shells.join(" ")
+    }
+
+    /// The largest noble gas whose shells are all contained in this
+    /// configuration, with fewer electrons.
+    pub fn noble_gas_core(&self) -> Option<ElementSymbol> {
+        NOBLE_GASES.iter().rev().copied().find(|&gas| {
+            let core = Self::ground_state(gas);
+            core.electrons() < self.electrons()
+                && core
+                    .shells
+                    .iter()
+                    .all(|shell| self.occupation(shell.n, shell.l) == shell.electrons)
+        })
+    }
+
+    /// Set the occupation of a shell, keeping the order and dropping empty
+    /// shells.
+    fn set(&mut self, n: u8, l: AngularMomentum, electrons: u8) {
+        let key = |shell: &Shell| (shell.n, shell.l);
+        match self.shells.binary_search_by_key(&(n, l), key) {
+            Ok(i) if electrons == 0 => {
+                self.shells.remove(i);
+            }
+            Ok(i) => self.shells[i].electrons = electrons,
+            Err(_) if electrons == 0 => {}
+            Err(i) => self.shells.insert(i, Shell::new(n, l, electrons)),
+        }
+    }
+}
+
+impl Display for ElectronConfiguration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(gas) = self.noble_gas_core() else {
+            return f.write_str(&self.full_notation());
+        };
+        let core = Self::ground_state(gas);
+        write!(f, "[{gas}]")?;
+        for shell in self
+            .shells
+            .iter()
+            .filter(|shell| !core.shells.contains(shell))
+        {
+            write!(f, " {shell}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Shells separated by spaces or dots, optionally after a noble-gas core,
+/// e.g. `1s2 2s2 2p2`, `[Ar] 3d6 4s2` or `[Kr].4d10`.
+impl FromStr for ElectronConfiguration {
+    type Err = ConfigurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (core, rest) = match s.strip_prefix('[') {
+            Some(rest) => {
+                let (gas, rest) = rest
+                    .split_once(']')
+                    .ok_or_else(|| ConfigurationError::InvalidCore(s.to_string()))?;
+                let gas = ElementSymbol::from_str(gas)
+                    .ok()
+                    .filter(|gas| NOBLE_GASES.contains(gas))
+                    .ok_or_else(|| ConfigurationError::InvalidCore(gas.to_string()))?;
+                (Self::ground_state(gas), rest)
+            }
+            None => (Self::default(), s),
+        };
+        let shells = rest
+            .split(|c: char| c.is_whitespace() || c == '.')
+            .filter(|token| !token.is_empty())
+            .map(Shell::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(core.shells.into_iter().chain(shells))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigurationError {
+    InvalidShell(String),
+    /// Only noble gases can be written as a core.
+    InvalidCore(String),
+    DuplicateShell(u8, AngularMomentum),
+    /// The valence charge does not fill whole outer shells.
+    ValenceMismatch {
+        configuration: String,
+        valence_charge: u8,
+    },
+    /// The potential of the element has no valence charge in the table.
+    UnknownValenceCharge(ElementSymbol),
+}
+
+impl Display for ConfigurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigurationError::InvalidShell(shell) => write!(f, "invalid shell `{shell}`"),
+            ConfigurationError::InvalidCore(core) => {
+                write!(f, "invalid core `{core}`, expected a noble gas")
+            }
+            ConfigurationError::DuplicateShell(n, l) => {
+                write!(f, "shell {n}{} is given twice", l.letter())
+            }
+            ConfigurationError::ValenceMismatch {
+                configuration,
+                valence_charge,
+            } => write!(
+                f,
+                "{valence_charge} valence electrons do not fill the outer shells of {configuration}"
+            ),
+            ConfigurationError::UnknownValenceCharge(symbol) => {
+                write!(
+                    f,
+                    "the valence charge of the {symbol} potential is not known"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigurationError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{AngularMomentum, ElementSymbol},
+    };
+
+    use super::{ConfigurationError, ElectronConfiguration, Shell};
+
+    #[test]
+    fn test_ground_states() {
+        use ElementSymbol::*;
+        let cases = [
+            (H, "1s1"),
+            (Ne, "[He] 2s2 2p6"),
+            (Fe, "[Ar] 3d6 4s2"),
+            (Cr, "[Ar] 3d5 4s1"),
+            (Ga, "[Ar] 3d10 4s2 4p1"),
+            (Pd, "[Kr] 4d10"),
+            (Gd, "[Xe] 4f7 5d1 6s2"),
+            (Lu, "[Xe] 4f14 5d1 6s2"),
+            (Au, "[Xe] 4f14 5d10 6s1"),
+            (U, "[Rn] 5f3 6d1 7s2"),
+            (Lr, "[Rn] 5f14 7s2 7p1"),
+        ];
+        for (symbol, expected) in cases {
+            let configuration = ElectronConfiguration::ground_state(symbol);
+            assert_eq!(configuration.to_string(), expected);
+            let parsed: ElectronConfiguration = expected.parse().unwrap();
+            assert_eq!(parsed, configuration);
+        }
+        for elm in ELEMENT_TABLE.iter() {
+            let configuration = ElectronConfiguration::ground_state(elm.symbol());
+            assert_eq!(
+                configuration.electrons(),
+                elm.atomic_number(),
+                "{}",
+                elm.symbol()
+            );
+        }
+        assert_eq!(
+            ElectronConfiguration::ground_state(Na).full_notation(),
+            "1s2 2s2 2p6 3s1"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let c: ElectronConfiguration = "1s2 2s2 2p2".parse().unwrap();
+        assert_eq!(c, ElectronConfiguration::ground_state(ElementSymbol::C));
+        let pd: ElectronConfiguration = "[Kr].4d10".parse().unwrap();
+        assert_eq!(pd.occupation(4, AngularMomentum::D), 10);
+        for input in ["[Fe] 4p1", "[Ar 3d6", "2d1", "2p7", "1s2 1s2", "3x2", "3d"] {
+            assert!(input.parse::<ElectronConfiguration>().is_err(), "{input}");
+        }
+        assert_eq!("4f14".parse::<Shell>().unwrap().capacity(), 14);
+    }
+
+    #[test]
+    fn test_valence_justifies_lcao() {
+        for elm in ELEMENT_TABLE
+            .iter()
+            .filter(|elm| elm.valence_charge().is_some())
+        {
+            let valence = ElectronConfiguration::valence_of(&ELEMENT_TABLE, elm.symbol())
+                .unwrap_or_else(|e| panic!("{}: {e}", elm.symbol()));
+            let shells: u8 = valence.channel_shells().iter().sum();
+            // Every occupied valence shell has an LCAO state; the rest are
+            // empty polarisation shells such as 4p for the 3d metals.
+            assert!(elm.lcao() >= Some(shells), "{}: {valence}", elm.symbol());
+        }
+        // The core-corrected Y potential keeps 4s and 4p in the core.
+        let y = ElectronConfiguration::valence_of(&ELEMENT_TABLE, ElementSymbol::Y).unwrap();
+        assert_eq!(y.full_notation(), "4d1 5s2");
+        assert_eq!(
+            ElectronConfiguration::valence_of(&ELEMENT_TABLE, ElementSymbol::Lu),
+            Err(ConfigurationError::UnknownValenceCharge(ElementSymbol::Lu))
+        );
+        let lu = ElectronConfiguration::ground_state(ElementSymbol::Lu)
+            .valence(11)
+            .unwrap();
+        assert_eq!(lu.full_notation(), "5s2 5p6 5d1 6s2");
+        assert_eq!(lu.channel_electrons(), [4, 6, 1, 0]);
+        assert!(ElectronConfiguration::ground_state(ElementSymbol::Fe)
+            .valence(7)
+            .is_err());
+    }
+}
@@ -1,16 +1,27 @@
-use std::{fmt::Display, str::FromStr};
+use std::{borrow::Cow, fmt::Display, str::FromStr};
 
-use crate::data::ELEMENT_TABLE;
+use serde::{Deserialize, Serialize};
 
-use super::{element_symbol::SymbolError, ElementSymbol};
+use crate::data::{table_index_of, ELEMENT_TABLE};
+
+use super::{
+    element_symbol::{Block, ElementCategory, SymbolError},
+    otfg::{OtfgDefinition, OtfgError},
+    ElementSymbol,
+};
 
 #[derive(Debug, Clone)]
 pub struct Element {
     pub symbol: ElementSymbol,
     pub atomic_number: u8,
-    pub lcao: u8,
+    /// Number of LCAO states of the potential, `None` when not known.
+    pub lcao: Option<u8>,
     pub mass: f64,
-    pub potential: &'static str,
+    pub potential: Potential,
+    /// Valence charge (Zval) of the potential, `None` when not known.
+    pub valence_charge: Option<u8>,
+    /// Suggested cutoffs of the potential, `None` when not known.
+    pub cutoffs: Option<Cutoffs>,
     pub spin: u8,
     pub covalent_radius: Option<f64>,
 }
@@ -27,7 +38,7 @@ impl Element {
         self.atomic_number
     }
 
-    pub fn lcao(&self) -> u8 {
+    pub fn lcao(&self) -> Option<u8> {
         self.lcao
     }
 
@@ -35,19 +46,80 @@ impl Element {
         self.mass
     }
 
-    pub fn potential(&self) -> &str {
-        self.potential
+    pub fn potential(&self) -> &Potential {
+        &self.potential
+    }
+
+    pub fn valence_charge(&self) -> Option<u8> {
+        self.valence_charge
+    }
+
+    pub fn cutoffs(&self) -> Option<Cutoffs> {
+        self.cutoffs
+    }
+
+    /// Recommended cutoff energy in eV at the given basis precision.
+    pub fn cutoff_energy(&self, precision: BasisPrecision) -> Option<f64> {
+        self.cutoffs.map(|cutoffs| cutoffs.energy(precision))
     }
 
     pub fn spin(&self) -> u8 {
         self.spin
     }
 
+    /// Pyykko single-bond covalent radius in angstrom. Other sets are in
+    /// `radius`.
     pub fn covalent_radius(&self) -> Option<f64> {
         self.covalent_radius
     }
 }
 
+/// Recommended plane-wave cutoff energies of a potential, in eV.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cutoffs {
+    pub coarse: f64,
+    pub medium: f64,
+    pub fine: f64,
+}
+
+impl Cutoffs {
+    /// `PRECISE` and `EXTREME` are 1.2 and 1.6 times the fine cutoff, as
+    /// in CASTEP.
+    pub fn energy(&self, precision: BasisPrecision) -> f64 {
+        match precision {
+            BasisPrecision::Coarse => self.coarse,
+            BasisPrecision::Medium => self.medium,
+            BasisPrecision::Fine => self.fine,
+            BasisPrecision::Precise => self.fine * 1.2,
+            BasisPrecision::Extreme => self.fine * 1.6,
+        }
+    }
+}
+
+/// Values of the CASTEP `BASIS_PRECISION` keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BasisPrecision {
+    Coarse,
+    Medium,
+    Fine,
+    Precise,
+    Extreme,
+}
+
+impl Display for BasisPrecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            BasisPrecision::Coarse => "COARSE",
+            BasisPrecision::Medium => "MEDIUM",
+            BasisPrecision::Fine => "FINE",
+            BasisPrecision::Precise => "PRECISE",
+            BasisPrecision::Extreme => "EXTREME",
+        };
+        f.write_str(name)
+    }
+}
+
 impl Eq for Element {}
 
 impl PartialEq for Element {
@@ -83,6 +155,335 @@ impl Display for Element {
     }
 }
 
+/// The pseudopotential of a species, in any form CASTEP accepts after the
+/// species label in a `SPECIES_POT` block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Potential {
+    /// A pseudopotential file, e.g. `C_00PBE.usp`.
+    File(PotentialFile),
+    /// An on-the-fly generation (OTFG) definition,
+    /// e.g. `2|1.4|10|12|13|20:21(qc=6)`.
+    Otfg(Box<OtfgDefinition>),
+    /// A named OTFG library, e.g. `C19`.
+    Library(OtfgLibrary),
+}
+
+impl Potential {
+    pub fn kind(&self) -> PotentialKind {
+        match self {
+            Potential::File(file) => file.kind(),
+            Potential::Otfg(_) => PotentialKind::OnTheFly,
+            Potential::Library(library) => library.kind(),
+        }
+    }
+
+    pub fn as_file(&self) -> Option<&PotentialFile> {
+        match self {
+            Potential::File(file) => Some(file),
+            _ => None,
+        }
+    }
+
+    pub fn as_otfg(&self) -> Option<&OtfgDefinition> {
+        match self {
+            Potential::Otfg(definition) => Some(definition),
+            _ => None,
+        }
+    }
+
+    /// Functional of a potential file. On-the-fly potentials are generated
+    /// with the `XC_FUNCTIONAL` of the calculation, so they have none.
+    pub fn functional(&self) -> Option<Functional> {
+        self.as_file().and_then(PotentialFile::functional)
+    }
+}
+
+impl Display for Potential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Potential::File(file) => write!(f, "{}", file),
+            Potential::Otfg(definition) => write!(f, "{}", definition),
+            Potential::Library(library) => write!(f, "{}", library),
+        }
+    }
+}
+
+impl FromStr for Potential {
+    type Err = PotentialError;
+
+    /// Library names are matched case-insensitively, strings containing `|`
+    /// are OTFG definitions, and anything else must be a file name with a
+    /// known pseudopotential extension.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            Err(PotentialError::Empty)
+        } else if let Ok(library) = OtfgLibrary::from_str(s) {
+            Ok(Potential::Library(library))
+        } else if s.contains('|') {
+            OtfgDefinition::from_str(s)
+                .map(|definition| Potential::Otfg(Box::new(definition)))
+                .map_err(PotentialError::Otfg)
+        } else {
+            PotentialFile::from_str(s).map(Potential::File)
+        }
+    }
+}
+
+/// A pseudopotential stored on disk, e.g. `C_00PBE.usp` or `Y_00PBE.uspcc`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PotentialFile {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) format: PotentialFormat,
+}
+
+impl PotentialFile {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn format(&self) -> PotentialFormat {
+        self.format
+    }
+
+    pub fn kind(&self) -> PotentialKind {
+        self.format.kind()
+    }
+
+    /// The tag after the last `_` of the file stem, e.g. `00PBE` in
+    /// `C_00PBE.usp`. Empty if the name has no tag.
+    fn tag(&self) -> &str {
+        let file_name = self.name.rsplit(['/', '\\']).next().unwrap_or(&self.name);
+        let stem = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _ext)| stem);
+        stem.rsplit_once('_').map_or("", |(_prefix, tag)| tag)
+    }
+
+    /// Version digits of the potential, e.g. `00` in `C_00PBE.usp`.
+    pub fn version(&self) -> &str {
+        let tag = self.tag();
+        let digits = tag.find(|c: char| !c.is_ascii_digit()).unwrap_or(tag.len());
+        &tag[..digits]
+    }
+
+    /// Functional the potential was generated with. The historical `_00.usp`
+    /// files without a functional suffix are LDA potentials. Returns `None`
+    /// when the suffix is not recognised.
+    pub fn functional(&self) -> Option<Functional> {
+        let tag = self.tag();
+        let suffix = &tag[self.version().len()..];
+        if suffix.is_empty() && !tag.is_empty() {
+            Some(Functional::Lda)
+        } else {
+            Functional::from_str(suffix).ok()
+        }
+    }
+}
+
+impl Display for PotentialFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl FromStr for PotentialFile {
+    type Err = PotentialError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(PotentialError::Empty);
+        }
+        let format = s
+            .rsplit_once('.')
+            .and_then(|(_stem, ext)| PotentialFormat::from_extension(ext))
+            .ok_or_else(|| PotentialError::UnknownFormat(s.to_string()))?;
+        Ok(Self {
+            name: Cow::Owned(s.to_string()),
+            format,
+        })
+    }
+}
+
+/// File formats of pseudopotentials read by CASTEP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PotentialFormat {
+    /// `.usp`
+    Usp,
+    /// `.uspcc`, ultrasoft with nonlinear core correction
+    Uspcc,
+    /// `.recpot`
+    Recpot,
+    /// `.ncp`
+    Ncp,
+}
+
+impl PotentialFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "usp" => Some(PotentialFormat::Usp),
+            "uspcc" => Some(PotentialFormat::Uspcc),
+            "recpot" => Some(PotentialFormat::Recpot),
+            "ncp" => Some(PotentialFormat::Ncp),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PotentialFormat::Usp => "usp",
+            PotentialFormat::Uspcc => "uspcc",
+            PotentialFormat::Recpot => "recpot",
+            PotentialFormat::Ncp => "ncp",
+        }
+    }
+
+    pub fn kind(&self) -> PotentialKind {
+        match self {
+            PotentialFormat::Usp | PotentialFormat::Uspcc => PotentialKind::Ultrasoft,
+            PotentialFormat::Recpot | PotentialFormat::Ncp => PotentialKind::NormConserving,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PotentialKind {
+    Ultrasoft,
+    NormConserving,
+    /// Generated by CASTEP at runtime; whether it is ultrasoft or
+    /// norm-conserving is decided by the definition string.
+    OnTheFly,
+}
+
+/// Exchange-correlation functionals encoded in pseudopotential file names.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Functional {
+    Lda,
+    Pw91,
+    Pbe,
+    PbeSol,
+    Rpbe,
+    Wc,
+}
+
+impl Display for Functional {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Functional::Lda => "LDA",
+            Functional::Pw91 => "PW91",
+            Functional::Pbe => "PBE",
+            Functional::PbeSol => "PBESOL",
+            Functional::Rpbe => "RPBE",
+            Functional::Wc => "WC",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Functional {
+    type Err = PotentialError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "LDA" => Ok(Functional::Lda),
+            "PW91" => Ok(Functional::Pw91),
+            "PBE" => Ok(Functional::Pbe),
+            "PBESOL" => Ok(Functional::PbeSol),
+            "RPBE" => Ok(Functional::Rpbe),
+            "WC" => Ok(Functional::Wc),
+            _ => Err(PotentialError::UnknownFunctional(s.to_string())),
+        }
+    }
+}
+
+/// Named OTFG libraries built into CASTEP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OtfgLibrary {
+    C17,
+    C18,
+    C19,
+    Ncp,
+    Ncp17,
+    Ncp18,
+    Ncp19,
+    Qc5,
+    Soc19,
+}
+
+impl OtfgLibrary {
+    pub fn kind(&self) -> PotentialKind {
+        match self {
+            OtfgLibrary::Ncp | OtfgLibrary::Ncp17 | OtfgLibrary::Ncp18 | OtfgLibrary::Ncp19 => {
+                PotentialKind::NormConserving
+            }
+            _ => PotentialKind::Ultrasoft,
+        }
+    }
+}
+
+impl Display for OtfgLibrary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OtfgLibrary::C17 => "C17",
+            OtfgLibrary::C18 => "C18",
+            OtfgLibrary::C19 => "C19",
+            OtfgLibrary::Ncp => "NCP",
+            OtfgLibrary::Ncp17 => "NCP17",
+            OtfgLibrary::Ncp18 => "NCP18",
+            OtfgLibrary::Ncp19 => "NCP19",
+            OtfgLibrary::Qc5 => "QC5",
+            OtfgLibrary::Soc19 => "SOC19",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for OtfgLibrary {
+    type Err = PotentialError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "C17" => Ok(OtfgLibrary::C17),
+            "C18" => Ok(OtfgLibrary::C18),
+            "C19" => Ok(OtfgLibrary::C19),
+            "NCP" => Ok(OtfgLibrary::Ncp),
+            "NCP17" => Ok(OtfgLibrary::Ncp17),
+            "NCP18" => Ok(OtfgLibrary::Ncp18),
+            "NCP19" => Ok(OtfgLibrary::Ncp19),
+            "QC5" => Ok(OtfgLibrary::Qc5),
+            "SOC19" => Ok(OtfgLibrary::Soc19),
+            _ => Err(PotentialError::UnknownLibrary(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PotentialError {
+    Empty,
+    UnknownFormat(String),
+    UnknownFunctional(String),
+    UnknownLibrary(String),
+    Otfg(OtfgError),
+}
+
+impl Display for PotentialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PotentialError::Empty => f.write_str("empty potential specification"),
+            PotentialError::UnknownFormat(s) => {
+                write!(f, "unknown pseudopotential file format: {s}")
+            }
+            PotentialError::UnknownFunctional(s) => write!(f, "unknown functional: {s}"),
+            PotentialError::UnknownLibrary(s) => write!(f, "unknown OTFG library: {s}"),
+            PotentialError::Otfg(e) => write!(f, "invalid OTFG definition: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for PotentialError {}
+
 pub trait LookupElement {
     /// Breaking change:
     /// Because now the input is strictly confined by enum `ElementSymbol` and
@@ -90,18 +491,25 @@ pub trait LookupElement {
     /// fail. No need to return `Option<&Element>`.
     fn get_by_symbol(&self, symbol: ElementSymbol) -> &Element;
     fn get_by_atomic_number(&self, atomic_number: u8) -> Option<&Element>;
+    /// Lookup for callers written when hydrogen was stored as atomic number
+    /// 0. Both 0 and 1 give hydrogen.
+    #[deprecated(note = "hydrogen is atomic number 1; use `get_by_atomic_number`")]
+    fn get_by_legacy_atomic_number(&self, atomic_number: u8) -> Option<&Element> {
+        self.get_by_atomic_number(atomic_number.max(1))
+    }
 }
 
+/// The array must be ordered by atomic number like `ELEMENT_TABLE`, so that
+/// lookups index directly into it.
 impl LookupElement for [Element; 103] {
     fn get_by_symbol(&self, symbol: ElementSymbol) -> &Element {
-        self.iter()
-            .find(|item| item.symbol() == symbol)
-            .expect("Internal error. This function should not failed.")
+        let element = &self[symbol.table_index()];
+        debug_assert_eq!(element.symbol(), symbol);
+        element
     }
 
     fn get_by_atomic_number(&self, atomic_number: u8) -> Option<&Element> {
-        self.iter()
-            .find(|item| item.atomic_number() == atomic_number)
+        table_index_of(atomic_number).map(|index| &self[index])
     }
 }
 
@@ -112,6 +520,69 @@ pub trait Conventions {
     fn rare_earth_la(&self) -> &[Element];
 }
 
+/// Selection of elements by IUPAC classification, for tables holding all
+/// 103 elements.
+pub trait Classification {
+    /// All elements, ordered by atomic number.
+    fn elements(&self) -> &[Element];
+
+    /// La to Lu
+    fn lanthanides(&self) -> &[Element] {
+        &self.elements()[56..71]
+    }
+
+    /// Ac to Lr
+    fn actinides(&self) -> &[Element] {
+        &self.elements()[88..103]
+    }
+
+    /// Elements of the given period, from 1 to 7. Empty for other numbers.
+    fn period(&self, period: u8) -> &[Element] {
+        let elements = self.elements();
+        let start = elements.partition_point(|elm| elm.symbol().period() < period);
+        let end = elements.partition_point(|elm| elm.symbol().period() <= period);
+        &elements[start..end]
+    }
+
+    fn group(&self, group: u8) -> Vec<&Element> {
+        self.elements()
+            .iter()
+            .filter(|elm| elm.symbol().group() == Some(group))
+            .collect()
+    }
+
+    fn block(&self, block: Block) -> Vec<&Element> {
+        self.elements()
+            .iter()
+            .filter(|elm| elm.symbol().block() == block)
+            .collect()
+    }
+
+    fn category(&self, category: ElementCategory) -> Vec<&Element> {
+        self.elements()
+            .iter()
+            .filter(|elm| elm.symbol().category() == category)
+            .collect()
+    }
+
+    /// Elements whose symbol satisfies `predicate`.
+    fn filter<P: Fn(ElementSymbol) -> bool>(&self, predicate: P) -> Vec<&Element>
+    where
+        Self: Sized,
+    {
+        self.elements()
+            .iter()
+            .filter(|elm| predicate(elm.symbol()))
+            .collect()
+    }
+}
+
+impl Classification for [Element; 103] {
+    fn elements(&self) -> &[Element] {
+        self
+    }
+}
+
 impl Conventions for [Element; 103] {
     fn metals_3d(&self) -> &[Element] {
         &self[20..30]
@@ -129,3 +600,112 @@ impl Conventions for [Element; 103] {
         &self[56..71]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{
+        data::{element, element_by_atomic_number, ELEMENT_TABLE},
+        element::ElementSymbol,
+    };
+
+    use super::{
+        Element, Functional, LookupElement, OtfgLibrary, Potential, PotentialFormat, PotentialKind,
+    };
+
+    #[test]
+    fn test_lookup() {
+        for elm in ELEMENT_TABLE.iter() {
+            assert_eq!(ELEMENT_TABLE.get_by_symbol(elm.symbol()), elm);
+            assert_eq!(element(elm.symbol()).symbol(), elm.symbol());
+            assert_eq!(
+                element_by_atomic_number(elm.atomic_number())
+                    .unwrap()
+                    .symbol(),
+                elm.symbol()
+            );
+        }
+        assert!(ELEMENT_TABLE.get_by_atomic_number(104).is_none());
+        assert!(ELEMENT_TABLE.get_by_atomic_number(0).is_none());
+        assert_eq!(ELEMENT_TABLE.len(), 103);
+        for (i, elm) in ELEMENT_TABLE.iter().enumerate() {
+            assert_eq!(elm.symbol() as u8, elm.atomic_number());
+            assert_eq!(elm.atomic_number() as usize, i + 1);
+        }
+        #[allow(deprecated)]
+        let hydrogen = ELEMENT_TABLE.get_by_legacy_atomic_number(0).unwrap();
+        assert_eq!(hydrogen.symbol(), ElementSymbol::H);
+        const IRON: &Element = element(ElementSymbol::Fe);
+        assert_eq!(IRON.atomic_number(), 26);
+    }
+
+    #[test]
+    fn test_conventions() {
+        use crate::element::{Block, Classification, ElementCategory};
+        assert_eq!(ELEMENT_TABLE.lanthanides().len(), 15);
+        assert_eq!(ELEMENT_TABLE.actinides()[0].symbol(), ElementSymbol::Ac);
+        assert_eq!(ELEMENT_TABLE.period(1).len(), 2);
+        assert_eq!(ELEMENT_TABLE.period(6).len(), 32);
+        assert_eq!(ELEMENT_TABLE.period(7).len(), 17);
+        assert!(ELEMENT_TABLE.period(8).is_empty());
+        let halogens: Vec<ElementSymbol> = ELEMENT_TABLE
+            .category(ElementCategory::Halogen)
+            .iter()
+            .map(|elm| elm.symbol())
+            .collect();
+        assert_eq!(
+            halogens,
+            [
+                ElementSymbol::F,
+                ElementSymbol::Cl,
+                ElementSymbol::Br,
+                ElementSymbol::I,
+                ElementSymbol::At
+            ]
+        );
+        assert_eq!(ELEMENT_TABLE.group(18).len(), 6);
+        assert_eq!(ELEMENT_TABLE.block(Block::F).len(), 28);
+        assert_eq!(ELEMENT_TABLE.block(Block::D).len(), 31);
+        assert_eq!(ELEMENT_TABLE.category(ElementCategory::Metalloid).len(), 6);
+        let noble = ELEMENT_TABLE.filter(|symbol| symbol.category() == ElementCategory::NobleGas);
+        assert_eq!(noble.len(), 6);
+        // Both traits can be used as trait objects.
+        let table: &dyn Classification = &ELEMENT_TABLE;
+        assert_eq!(table.block(Block::S).len(), 14);
+        let conventions: &dyn crate::element::Conventions = &ELEMENT_TABLE;
+        assert_eq!(conventions.metals_3d().len(), 10);
+    }
+
+    #[test]
+    fn test_potential() {
+        let c = ELEMENT_TABLE.get_by_symbol(ElementSymbol::C).potential();
+        let file = c.as_file().unwrap();
+        assert_eq!(file.format(), PotentialFormat::Usp);
+        assert_eq!(file.version(), "00");
+        assert_eq!(file.functional(), Some(Functional::Pbe));
+        assert_eq!(c.to_string(), "C_00PBE.usp");
+        let y = ELEMENT_TABLE.get_by_symbol(ElementSymbol::Y).potential();
+        assert_eq!(y.as_file().unwrap().format(), PotentialFormat::Uspcc);
+        assert_eq!(y.kind(), PotentialKind::Ultrasoft);
+        let he = ELEMENT_TABLE.get_by_symbol(ElementSymbol::He).potential();
+        assert_eq!(he.as_file().unwrap().functional(), Some(Functional::Lda));
+        let mg = ELEMENT_TABLE.get_by_symbol(ElementSymbol::Mg).potential();
+        assert_eq!(mg.as_file().unwrap().functional(), Some(Functional::Pw91));
+        let ac = ELEMENT_TABLE.get_by_symbol(ElementSymbol::Ac).potential();
+        assert_eq!(ac.kind(), PotentialKind::NormConserving);
+        for input in ["C19", "ncp19", "2|1.4|10|12|13|20:21(qc=6)", "O_00PBE.usp"] {
+            let potential = Potential::from_str(input).unwrap();
+            assert!(potential.to_string().eq_ignore_ascii_case(input));
+        }
+        assert_eq!(
+            Potential::from_str("qc5").unwrap(),
+            Potential::Library(OtfgLibrary::Qc5)
+        );
+        assert!(Potential::from_str("C_00PBE.txt").is_err());
+        assert!(Potential::from_str("  ").is_err());
+        assert!(Potential::from_str("2|1.4|10").is_err());
+        let otfg = Potential::from_str("2|1.4|10|12|13|20:21(qc=6)").unwrap();
+        assert_eq!(otfg.as_otfg().unwrap().projectors.len(), 2);
+    }
+}
@@ -9,8 +9,8 @@ pub use error::SymbolError;
 
 #[derive(Eq, PartialEq, PartialOrd, Ord, Debug, Serialize, Clone, Copy, Hash)]
 pub enum ElementSymbol {
-    H,
-    He = 2,
+    H = 1,
+    He,
     Li,
     Be,
     B,
@@ -116,7 +116,7 @@ pub enum ElementSymbol {
 
 impl Display for ElementSymbol {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        f.pad(&format!("{:?}", self))
     }
 }
 
@@ -127,6 +127,8 @@ impl PartialEq<&str> for ElementSymbol {
     }
 }
 
+/// Coarse grouping of `family()`. `ElementSymbol::category`, `block`,
+/// `group` and `period` give the full IUPAC classification.
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ElementFamily {
@@ -134,11 +136,120 @@ pub enum ElementFamily {
     TransitionMetal4d,
     TransitionMetal5d,
     RareEarthLa,
+    /// Actinides, which are not rare earths.
+    #[deprecated(note = "actinides are not rare earths; use `ElementCategory::Actinide`")]
     RareEarthAc,
     Else,
 }
 
+/// Block of the periodic table, following the IUPAC layout with lutetium
+/// and lawrencium in group 3 of the d-block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Block {
+    S,
+    P,
+    D,
+    F,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementCategory {
+    AlkaliMetal,
+    AlkalineEarthMetal,
+    TransitionMetal,
+    PostTransitionMetal,
+    Metalloid,
+    /// Reactive nonmetals other than the halogens.
+    Nonmetal,
+    Halogen,
+    NobleGas,
+    /// La to Lu
+    Lanthanide,
+    /// Ac to Lr
+    Actinide,
+}
+
+/// Atomic number of the last element of each period.
+const PERIOD_ENDS: [u8; 7] = [2, 10, 18, 36, 54, 86, 118];
+
 impl ElementSymbol {
+    /// Slot of the element in `ELEMENT_TABLE`, which is ordered by atomic
+    /// number.
+    pub const fn table_index(self) -> usize {
+        self as usize - 1
+    }
+
+    /// Conversion for callers written when hydrogen was stored as atomic
+    /// number 0. Both 0 and 1 give hydrogen.
+    #[deprecated(note = "hydrogen is atomic number 1; use `ElementSymbol::try_from`")]
+    pub fn from_legacy_atomic_number(atomic_number: u8) -> Result<Self, SymbolError> {
+        match atomic_number {
+            0 => Ok(ElementSymbol::H),
+            n => ElementSymbol::try_from(n),
+        }
+    }
+
+    pub fn period(&self) -> u8 {
+        let atomic_number = *self as u8;
+        PERIOD_ENDS
+            .iter()
+            .position(|&end| atomic_number <= end)
+            .expect("Internal error. Every element is in a period.") as u8
+            + 1
+    }
+
+    /// IUPAC group from 1 to 18. The f-block elements La to Yb and Ac to No
+    /// have no group.
+    pub fn group(&self) -> Option<u8> {
+        let atomic_number = *self as u8;
+        let period = self.period();
+        let position = match period {
+            1 => return Some(if atomic_number == 1 { 1 } else { 18 }),
+            _ => atomic_number - PERIOD_ENDS[period as usize - 2],
+        };
+        match (period, position) {
+            (_, 1..=2) => Some(position),
+            (2..=3, _) => Some(position + 10),
+            (4..=5, _) => Some(position),
+            (_, 3..=16) => None,
+            (_, _) => Some(position - 14),
+        }
+    }
+
+    pub fn block(&self) -> Block {
+        match self.group() {
+            _ if *self == ElementSymbol::He => Block::S,
+            Some(1..=2) => Block::S,
+            Some(3..=12) => Block::D,
+            Some(_) => Block::P,
+            None => Block::F,
+        }
+    }
+
+    pub fn category(&self) -> ElementCategory {
+        use ElementSymbol::*;
+        match self {
+            La | Ce | Pr | Nd | Pm | Sm | Eu | Gd | Tb | Dy | Ho | Er | Tm | Yb | Lu => {
+                ElementCategory::Lanthanide
+            }
+            Ac | Th | Pa | U | Np | Pu | Am | Cm | Bk | Cf | Es | Fm | Md | No | Lr => {
+                ElementCategory::Actinide
+            }
+            H | C | N | O | P | S | Se => ElementCategory::Nonmetal,
+            B | Si | Ge | As | Sb | Te => ElementCategory::Metalloid,
+            Al | Ga | In | Sn | Tl | Pb | Bi | Po => ElementCategory::PostTransitionMetal,
+            _ => match self.group() {
+                Some(1) => ElementCategory::AlkaliMetal,
+                Some(2) => ElementCategory::AlkalineEarthMetal,
+                Some(17) => ElementCategory::Halogen,
+                Some(18) => ElementCategory::NobleGas,
+                _ => ElementCategory::TransitionMetal,
+            },
+        }
+    }
+
+    #[allow(deprecated)]
     pub fn family(&self) -> ElementFamily {
         let atomic_number = *self as usize;
         match atomic_number {
@@ -169,5 +280,157 @@ mod test {
         let symbol = ElementSymbol::try_from(input);
         assert!(symbol.is_ok());
         println!("{:?}", symbol.unwrap());
+        assert_eq!(ElementSymbol::try_from(1_u8).unwrap(), ElementSymbol::H);
+        assert!(ElementSymbol::try_from(0_u8).is_err());
+        assert_eq!(ElementSymbol::from_str("1").unwrap(), ElementSymbol::H);
+    }
+
+    #[test]
+    fn test_classification() {
+        use super::{Block, ElementCategory};
+        let cases = [
+            (
+                ElementSymbol::H,
+                1,
+                Some(1),
+                Block::S,
+                ElementCategory::Nonmetal,
+            ),
+            (
+                ElementSymbol::He,
+                1,
+                Some(18),
+                Block::S,
+                ElementCategory::NobleGas,
+            ),
+            (
+                ElementSymbol::Na,
+                3,
+                Some(1),
+                Block::S,
+                ElementCategory::AlkaliMetal,
+            ),
+            (
+                ElementSymbol::Ca,
+                4,
+                Some(2),
+                Block::S,
+                ElementCategory::AlkalineEarthMetal,
+            ),
+            (
+                ElementSymbol::Al,
+                3,
+                Some(13),
+                Block::P,
+                ElementCategory::PostTransitionMetal,
+            ),
+            (
+                ElementSymbol::Si,
+                3,
+                Some(14),
+                Block::P,
+                ElementCategory::Metalloid,
+            ),
+            (
+                ElementSymbol::Cl,
+                3,
+                Some(17),
+                Block::P,
+                ElementCategory::Halogen,
+            ),
+            (
+                ElementSymbol::Fe,
+                4,
+                Some(8),
+                Block::D,
+                ElementCategory::TransitionMetal,
+            ),
+            (
+                ElementSymbol::Ag,
+                5,
+                Some(11),
+                Block::D,
+                ElementCategory::TransitionMetal,
+            ),
+            (
+                ElementSymbol::La,
+                6,
+                None,
+                Block::F,
+                ElementCategory::Lanthanide,
+            ),
+            (
+                ElementSymbol::Lu,
+                6,
+                Some(3),
+                Block::D,
+                ElementCategory::Lanthanide,
+            ),
+            (
+                ElementSymbol::Hf,
+                6,
+                Some(4),
+                Block::D,
+                ElementCategory::TransitionMetal,
+            ),
+            (
+                ElementSymbol::At,
+                6,
+                Some(17),
+                Block::P,
+                ElementCategory::Halogen,
+            ),
+            (
+                ElementSymbol::Rn,
+                6,
+                Some(18),
+                Block::P,
+                ElementCategory::NobleGas,
+            ),
+            (
+                ElementSymbol::Fr,
+                7,
+                Some(1),
+                Block::S,
+                ElementCategory::AlkaliMetal,
+            ),
+            (
+                ElementSymbol::U,
+                7,
+                None,
+                Block::F,
+                ElementCategory::Actinide,
+            ),
+            (
+                ElementSymbol::Lr,
+                7,
+                Some(3),
+                Block::D,
+                ElementCategory::Actinide,
+            ),
+        ];
+        for (symbol, period, group, block, category) in cases {
+            assert_eq!(symbol.period(), period, "{symbol}");
+            assert_eq!(symbol.group(), group, "{symbol}");
+            assert_eq!(symbol.block(), block, "{symbol}");
+            assert_eq!(symbol.category(), category, "{symbol}");
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_atomic_number() {
+        assert_eq!(
+            ElementSymbol::from_legacy_atomic_number(0).unwrap(),
+            ElementSymbol::H
+        );
+        assert_eq!(
+            ElementSymbol::from_legacy_atomic_number(1).unwrap(),
+            ElementSymbol::H
+        );
+        assert_eq!(
+            ElementSymbol::from_legacy_atomic_number(2).unwrap(),
+            ElementSymbol::He
+        );
     }
 }
@@ -0,0 +1,485 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::File,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::data::{table_index_of, ELEMENT_TABLE};
+
+use super::{
+    element_info::PotentialError,
+    potential_set::PotentialSet,
+    yaml_parser::{ElementOverride, ElementOverrideTable},
+    Classification, Conventions, Element, ElementSymbol, LookupElement, Potential,
+};
+
+/// Name of the layer holding the values of `ELEMENT_TABLE`.
+pub const BUILTIN_LAYER: &str = "built-in";
+
+/// Fields of `Element` that can be overridden at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ElementField {
+    Lcao,
+    Mass,
+    Potential,
+    ValenceCharge,
+    Cutoffs,
+    Spin,
+    CovalentRadius,
+}
+
+impl Display for ElementField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ElementField::Lcao => "LCAO",
+            ElementField::Mass => "mass",
+            ElementField::Potential => "potential",
+            ElementField::ValenceCharge => "valence_charge",
+            ElementField::Cutoffs => "cutoffs",
+            ElementField::Spin => "spin",
+            ElementField::CovalentRadius => "covalent_radius",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A runtime element table. It starts from `ELEMENT_TABLE` and applies
+/// override layers in order, later layers taking precedence. The layer each
+/// field value came from is recorded.
+/// # Example
+/// ```
+/// use castep_periodic_table::element::{ElementField, ElementSymbol, ElementTable, LookupElement};
+///
+/// let overrides = "Element_info:\n  - symbol: Fe\n    spin: 2\n";
+/// let table = ElementTable::builtin()
+///     .with_override_str("project", overrides)
+///     .unwrap();
+/// assert_eq!(table.get_by_symbol(ElementSymbol::Fe).spin(), 2);
+/// assert_eq!(table.source_of(ElementSymbol::Fe, ElementField::Spin), "project");
+/// assert_eq!(table.source_of(ElementSymbol::Fe, ElementField::Mass), "built-in");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ElementTable {
+    elements: Vec<Element>,
+    layers: Vec<String>,
+    sources: HashMap<(ElementSymbol, ElementField), usize>,
+}
+
+impl Default for ElementTable {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ElementTable {
+    pub fn builtin() -> Self {
+        Self {
+            elements: ELEMENT_TABLE.to_vec(),
+            layers: vec![BUILTIN_LAYER.to_string()],
+            sources: HashMap::new(),
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Element> {
+        self.elements.iter()
+    }
+
+    pub fn as_slice(&self) -> &[Element] {
+        &self.elements
+    }
+
+    /// Names of the applied layers, from the built-in data to the last
+    /// override.
+    pub fn layers(&self) -> &[String] {
+        &self.layers
+    }
+
+    /// Apply a set of overrides as a new layer named `layer`. Nothing is
+    /// changed if any entry is invalid.
+    ///
+    /// An entry that changes the potential describes a new file, so the LCAO
+    /// states, valence charge and cutoffs it does not give become unknown,
+    /// as with `with_potential_set`.
+    pub fn apply_overrides(
+        &mut self,
+        layer: impl Into<String>,
+        overrides: &[ElementOverride],
+    ) -> Result<(), TableError> {
+        let layer = layer.into();
+        let potentials = overrides
+            .iter()
+            .map(|entry| {
+                entry
+                    .potential
+                    .as_deref()
+                    .map(Potential::from_str)
+                    .transpose()
+                    .map_err(|error| TableError::Potential {
+                        layer: layer.clone(),
+                        symbol: entry.symbol,
+                        error,
+                    })
+            })
+            .collect::<Result<Vec<Option<Potential>>, TableError>>()?;
+        let layer_id = self.layers.len();
+        for (entry, potential) in overrides.iter().zip(potentials) {
+            let symbol = entry.symbol;
+            let element = &mut self.elements[symbol.table_index()];
+            let mut changed = Vec::new();
+            if let Some(lcao) = entry.lcao {
+                element.lcao = Some(lcao);
+                changed.push(ElementField::Lcao);
+            }
+            if let Some(mass) = entry.mass {
+                element.mass = mass;
+                changed.push(ElementField::Mass);
+            }
+            if let Some(potential) = potential {
+                if potential != element.potential {
+                    if entry.lcao.is_none() {
+                        element.lcao = None;
+                        changed.push(ElementField::Lcao);
+                    }
+                    if entry.valence_charge.is_none() {
+                        element.valence_charge = None;
+                        changed.push(ElementField::ValenceCharge);
+                    }
+                    if entry.cutoffs.is_none() {
+                        element.cutoffs = None;
+                        changed.push(ElementField::Cutoffs);
+                    }
+                }
+                element.potential = potential;
+                changed.push(ElementField::Potential);
+            }
+            if let Some(valence_charge) = entry.valence_charge {
+                element.valence_charge = Some(valence_charge);
+                changed.push(ElementField::ValenceCharge);
+            }
+            if let Some(cutoffs) = entry.cutoffs {
+                element.cutoffs = Some(cutoffs);
+                changed.push(ElementField::Cutoffs);
+            }
+            if let Some(spin) = entry.spin {
+                element.spin = spin;
+                changed.push(ElementField::Spin);
+            }
+            if let Some(radius) = entry.covalent_radius {
+                element.covalent_radius = Some(radius);
+                changed.push(ElementField::CovalentRadius);
+            }
+            for field in changed {
+                self.sources.insert((symbol, field), layer_id);
+            }
+        }
+        self.layers.push(layer);
+        Ok(())
+    }
+
+    /// Use the potentials of `set`, as a layer named after it. The LCAO
+    /// states, valence charge and cutoffs given by the set replace those of
+    /// the table, and become unknown where the set does not give them.
+    /// Elements without a potential in the set are unchanged.
+    pub fn with_potential_set(mut self, set: &PotentialSet) -> Self {
+        let layer_id = self.layers.len();
+        for (element, entry) in self.elements.iter_mut().zip(set.potentials()) {
+            let Some(entry) = entry else {
+                continue;
+            };
+            element.potential = entry.potential.clone();
+            element.lcao = entry.lcao;
+            element.valence_charge = entry.valence_charge;
+            element.cutoffs = entry.cutoffs;
+            for field in [
+                ElementField::Potential,
+                ElementField::Lcao,
+                ElementField::ValenceCharge,
+                ElementField::Cutoffs,
+            ] {
+                self.sources.insert((element.symbol, field), layer_id);
+            }
+        }
+        self.layers.push(set.name().to_string());
+        self
+    }
+
+    /// Apply an override layer written in the same format as
+    /// `element_table.yaml`, with only `symbol` required in each entry.
+    pub fn with_override_str(mut self, layer: &str, yaml: &str) -> Result<Self, TableError> {
+        let table: ElementOverrideTable =
+            serde_yaml::from_str(yaml).map_err(|error| TableError::Yaml {
+                layer: layer.to_string(),
+                error,
+            })?;
+        self.apply_overrides(layer, &table.elements)?;
+        Ok(self)
+    }
+
+    /// Apply an override file. The layer is named after the path.
+    pub fn with_override_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self, TableError> {
+        let path = path.as_ref();
+        let layer = path.display().to_string();
+        let file = File::open(path).map_err(|error| TableError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let table: ElementOverrideTable =
+            serde_yaml::from_reader(file).map_err(|error| TableError::Yaml {
+                layer: layer.clone(),
+                error,
+            })?;
+        self.apply_overrides(layer, &table.elements)?;
+        Ok(self)
+    }
+
+    /// Name of the layer that set `field` of `symbol`.
+    pub fn source_of(&self, symbol: ElementSymbol, field: ElementField) -> &str {
+        let layer_id = self.sources.get(&(symbol, field)).copied().unwrap_or(0);
+        &self.layers[layer_id]
+    }
+
+    /// Every field set by an override layer, ordered by element and field.
+    pub fn provenance(&self) -> Vec<FieldSource<'_>> {
+        let mut report: Vec<FieldSource> = self
+            .sources
+            .iter()
+            .map(|(&(symbol, field), &layer_id)| FieldSource {
+                symbol,
+                field,
+                layer: &self.layers[layer_id],
+            })
+            .collect();
+        report.sort_by_key(|source| (source.symbol, source.field));
+        report
+    }
+}
+
+/// Where the value of one field of an element came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSource<'a> {
+    pub symbol: ElementSymbol,
+    pub field: ElementField,
+    pub layer: &'a str,
+}
+
+impl Display for FieldSource<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}: {}", self.symbol, self.field, self.layer)
+    }
+}
+
+impl LookupElement for ElementTable {
+    fn get_by_symbol(&self, symbol: ElementSymbol) -> &Element {
+        &self.elements[symbol.table_index()]
+    }
+
+    fn get_by_atomic_number(&self, atomic_number: u8) -> Option<&Element> {
+        table_index_of(atomic_number).map(|index| &self.elements[index])
+    }
+}
+
+impl Classification for ElementTable {
+    fn elements(&self) -> &[Element] {
+        &self.elements
+    }
+}
+
+impl Conventions for ElementTable {
+    fn metals_3d(&self) -> &[Element] {
+        &self.elements[20..30]
+    }
+
+    fn metals_4d(&self) -> &[Element] {
+        &self.elements[38..48]
+    }
+
+    fn metals_5d(&self) -> &[Element] {
+        &self.elements[71..80]
+    }
+
+    fn rare_earth_la(&self) -> &[Element] {
+        &self.elements[56..71]
+    }
+}
+
+#[derive(Debug)]
+pub enum TableError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    Yaml {
+        layer: String,
+        error: serde_yaml::Error,
+    },
+    Potential {
+        layer: String,
+        symbol: ElementSymbol,
+        error: PotentialError,
+    },
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            TableError::Yaml { layer, error } => write!(f, "{layer}: {error}"),
+            TableError::Potential {
+                layer,
+                symbol,
+                error,
+            } => write!(f, "{layer}: {symbol}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TableError::Io { error, .. } => Some(error),
+            TableError::Yaml { error, .. } => Some(error),
+            TableError::Potential { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{
+            Conventions, ElementField, ElementSymbol, LookupElement, Potential, PotentialFormat,
+        },
+    };
+
+    use super::{ElementTable, BUILTIN_LAYER};
+
+    #[test]
+    fn test_layers() {
+        let site = "
+Element_info:
+  - symbol: Fe
+    spin: 2
+    potential: Fe_00PBE.usp
+  - symbol: O
+    mass: 17.999
+";
+        let project = "
+Element_info:
+  - symbol: Fe
+    spin: 5
+";
+        let table = ElementTable::builtin()
+            .with_override_str("site", site)
+            .unwrap()
+            .with_override_str("project", project)
+            .unwrap();
+        let fe = table.get_by_symbol(ElementSymbol::Fe);
+        assert_eq!(fe.spin(), 5);
+        assert_eq!(
+            fe.potential().as_file().unwrap().format(),
+            PotentialFormat::Usp
+        );
+        assert_eq!(
+            table.source_of(ElementSymbol::Fe, ElementField::Spin),
+            "project"
+        );
+        assert_eq!(
+            table.source_of(ElementSymbol::Fe, ElementField::Potential),
+            "site"
+        );
+        assert_eq!(
+            table.source_of(ElementSymbol::O, ElementField::Lcao),
+            BUILTIN_LAYER
+        );
+        assert_eq!(table.get_by_atomic_number(8).unwrap().mass(), 17.999);
+        assert_eq!(table.metals_3d()[5].symbol(), ElementSymbol::Fe);
+        let report: Vec<String> = table.provenance().iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            report,
+            [
+                "O.mass: site",
+                "Fe.LCAO: site",
+                "Fe.potential: site",
+                "Fe.valence_charge: site",
+                "Fe.cutoffs: site",
+                "Fe.spin: project"
+            ]
+        );
+        assert_eq!(table.layers(), ["built-in", "site", "project"]);
+    }
+
+    #[test]
+    fn test_potential_override() {
+        // A new potential drops the values of the old one it does not repeat.
+        let swap = "
+Element_info:
+  - symbol: Fe
+    potential: Fe_C19.usp
+    valence_charge: 16
+";
+        let table = ElementTable::builtin()
+            .with_override_str("site", swap)
+            .unwrap();
+        let fe = table.get_by_symbol(ElementSymbol::Fe);
+        assert_eq!(fe.valence_charge(), Some(16));
+        assert_eq!(fe.lcao(), None);
+        assert_eq!(fe.cutoffs(), None);
+        for field in [
+            ElementField::Potential,
+            ElementField::Lcao,
+            ElementField::ValenceCharge,
+            ElementField::Cutoffs,
+        ] {
+            assert_eq!(table.source_of(ElementSymbol::Fe, field), "site");
+        }
+        assert_eq!(
+            table.source_of(ElementSymbol::Fe, ElementField::Mass),
+            BUILTIN_LAYER
+        );
+        // Restating the same potential keeps them.
+        let same = "Element_info:\n  - symbol: O\n    potential: O_00PBE.usp\n";
+        let table = ElementTable::builtin()
+            .with_override_str("site", same)
+            .unwrap();
+        let o = table.get_by_symbol(ElementSymbol::O);
+        assert_eq!(
+            (o.lcao(), o.valence_charge()),
+            (
+                ELEMENT_TABLE.get_by_symbol(ElementSymbol::O).lcao(),
+                Some(6)
+            )
+        );
+        assert_eq!(
+            table.source_of(ElementSymbol::O, ElementField::Lcao),
+            BUILTIN_LAYER
+        );
+    }
+
+    #[test]
+    fn test_invalid_layer() {
+        let table = ElementTable::builtin();
+        let bad_potential = "Element_info:\n  - symbol: C\n    potential: C.txt\n";
+        assert!(table
+            .clone()
+            .with_override_str("bad", bad_potential)
+            .is_err());
+        let unknown_field = "Element_info:\n  - symbol: C\n    colour: black\n";
+        assert!(table
+            .clone()
+            .with_override_str("bad", unknown_field)
+            .is_err());
+        let unknown_symbol = "Element_info:\n  - symbol: Xx\n";
+        assert!(table.with_override_str("bad", unknown_symbol).is_err());
+        let otfg = "Element_info:\n  - symbol: C\n    potential: 2|1.4|10|12|13|20:21(qc=6)\n";
+        let table = ElementTable::builtin()
+            .with_override_str("otfg", otfg)
+            .unwrap();
+        assert!(matches!(
+            table.get_by_symbol(ElementSymbol::C).potential(),
+            Potential::Otfg(_)
+        ));
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt::Display;
+
+use super::{
+    configuration::madelung_order, AngularMomentum, ConfigurationError, ElectronConfiguration,
+    ElementSymbol, LookupElement,
+};
+
+/// LCAO states of a species by angular momentum channel: the number of s,
+/// p, d and f shells in the basis used for Mulliken analysis. CASTEP reads
+/// the total in `SPECIES_LCAO_STATES`.
+/// # Example
+/// ```
+/// use castep_periodic_table::data::ELEMENT_TABLE;
+/// use castep_periodic_table::element::{ElementSymbol, LcaoStates};
+///
+/// // 3d6 4s2 with an empty 4p shell.
+/// let fe = LcaoStates::for_element(&ELEMENT_TABLE, ElementSymbol::Fe).unwrap();
+/// assert_eq!(fe.to_string(), "1s 1p 1d");
+/// assert_eq!(fe.total(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LcaoStates {
+    channels: [u8; 4],
+}
+
+impl LcaoStates {
+    pub fn new(s: u8, p: u8, d: u8, f: u8) -> Self {
+        Self {
+            channels: [s, p, d, f],
+        }
+    }
+
+    /// Shells indexed by `l`.
+    pub fn channels(&self) -> [u8; 4] {
+        self.channels
+    }
+
+    pub fn get(&self, l: AngularMomentum) -> u8 {
+        self.channels[l.l() as usize]
+    }
+
+    /// The value written in `SPECIES_LCAO_STATES`.
+    pub fn total(&self) -> u8 {
+        self.channels.iter().sum()
+    }
+
+    /// `total` states for an atom with `configuration` and a potential with
+    /// `valence_charge`: the occupied valence shells, then the empty shells
+    /// that fill after the first of them, e.g. 4p for Fe `3d6 4s2` and 4f
+    /// for La `5s2 5p6 5d1 6s2`.
+    pub fn for_configuration(
+        configuration: &ElectronConfiguration,
+        valence_charge: u8,
+        total: u8,
+    ) -> Result<Self, LcaoError> {
+        let valence = configuration
+            .valence(valence_charge)
+            .map_err(LcaoError::Configuration)?;
+        let occupied = valence.channel_shells();
+        let required: u8 = occupied.iter().sum();
+        if total < required {
+            return Err(LcaoError::TooFewStates {
+                valence: valence.to_string(),
+                required,
+                given: total,
+            });
+        }
+        let is_occupied = |(n, l): (u8, AngularMomentum)| configuration.occupation(n, l) > 0;
+        let first_valence = madelung_order()
+            .position(|(n, l)| valence.occupation(n, l) > 0)
+            .map_or(0, |i| i + 1);
+        let mut states = Self { channels: occupied };
+        let empty = madelung_order()
+            .skip(first_valence)
+            .filter(|&shell| !is_occupied(shell))
+            .take((total - required) as usize);
+        for (_, l) in empty {
+            states.channels[l.l() as usize] += 1;
+        }
+        Ok(states)
+    }
+
+    /// The states given by `Element::lcao()` for the valence charge of the
+    /// element's potential.
+    pub fn for_element<T: LookupElement>(
+        table: &T,
+        symbol: ElementSymbol,
+    ) -> Result<Self, LcaoError> {
+        let element = table.get_by_symbol(symbol);
+        let valence_charge = element.valence_charge().ok_or(LcaoError::Configuration(
+            ConfigurationError::UnknownValenceCharge(symbol),
+        ))?;
+        let total = element.lcao().ok_or(LcaoError::UnknownStates(symbol))?;
+        Self::for_configuration(
+            &ElectronConfiguration::ground_state(symbol),
+            valence_charge,
+            total,
+        )
+    }
+
+    /// Every occupied shell of `valence` needs a state of its channel.
+    pub fn check(&self, valence: &ElectronConfiguration) -> Result<(), LcaoError> {
+        let occupied = valence.channel_shells();
+        for (l, (&given, &required)) in self.channels.iter().zip(occupied.iter()).enumerate() {
+            if given < required {
+                return Err(LcaoError::MissingChannel {
+                    valence: valence.to_string(),
+                    l: AngularMomentum::from_l(l as u8).expect("four channels"),
+                    required,
+                    given,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Written as the shells of each non-empty channel, e.g. `2s 1p 1d`.
+impl Display for LcaoStates {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let channels: Vec<String> = self
+            .channels
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(l, count)| {
+                let l = AngularMomentum::from_l(l as u8).expect("four channels");
+                format!("{count}{}", l.letter())
+            })
+            .collect();
+        f.write_str(&channels.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LcaoError {
+    /// The valence charge of the potential is not known or does not fill
+    /// whole shells.
+    Configuration(ConfigurationError),
+    /// Fewer states than occupied valence shells.
+    TooFewStates {
+        valence: String,
+        required: u8,
+        given: u8,
+    },
+    /// An occupied valence channel without enough states.
+    MissingChannel {
+        valence: String,
+        l: AngularMomentum,
+        required: u8,
+        given: u8,
+    },
+    /// The table has no number of LCAO states for the element.
+    UnknownStates(ElementSymbol),
+    /// An error of the species with this label.
+    Species {
+        label: String,
+        error: Box<LcaoError>,
+    },
+}
+
+impl Display for LcaoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LcaoError::Configuration(error) => write!(f, "{error}"),
+            LcaoError::TooFewStates {
+                valence,
+                required,
+                given,
+            } => write!(
+                f,
+                "{given} LCAO states cannot hold the {required} valence shells of {valence}"
+            ),
+            LcaoError::MissingChannel {
+                valence,
+                l,
+                required,
+                given,
+            } => write!(
+                f,
+                "{given} {} LCAO states for the {required} {} shells of {valence}",
+                l.letter(),
+                l.letter()
+            ),
+            LcaoError::UnknownStates(symbol) => {
+                write!(f, "the number of LCAO states of {symbol} is not known")
+            }
+            LcaoError::Species { label, error } => write!(f, "species {label}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LcaoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LcaoError::Configuration(error) => Some(error),
+            LcaoError::Species { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{AngularMomentum, ElectronConfiguration, ElementSymbol, LookupElement},
+    };
+
+    use super::{LcaoError, LcaoStates};
+
+    #[test]
+    fn test_builtin_states() {
+        for elm in ELEMENT_TABLE.iter() {
+            let states = LcaoStates::for_element(&ELEMENT_TABLE, elm.symbol());
+            match elm.valence_charge() {
+                Some(_) => assert_eq!(
+                    Some(states.unwrap().total()),
+                    elm.lcao(),
+                    "{}",
+                    elm.symbol()
+                ),
+                None => assert!(
+                    matches!(states, Err(LcaoError::Configuration(_))),
+                    "{}",
+                    elm.symbol()
+                ),
+            }
+        }
+        let na = LcaoStates::for_element(&ELEMENT_TABLE, ElementSymbol::Na).unwrap();
+        assert_eq!(na, LcaoStates::new(2, 2, 0, 0));
+        let la = ElectronConfiguration::ground_state(ElementSymbol::La);
+        let la = LcaoStates::for_configuration(&la, 11, 6).unwrap();
+        assert_eq!(la.to_string(), "2s 2p 1d 1f");
+    }
+
+    #[test]
+    fn test_check() {
+        let valence: ElectronConfiguration = "3d6 4s2".parse().unwrap();
+        assert!(LcaoStates::new(1, 1, 1, 0).check(&valence).is_ok());
+        let missing = LcaoStates::new(2, 1, 0, 0).check(&valence).unwrap_err();
+        assert!(matches!(
+            missing,
+            LcaoError::MissingChannel {
+                l: AngularMomentum::D,
+                required: 1,
+                given: 0,
+                ..
+            }
+        ));
+        let fe = ElectronConfiguration::ground_state(ElementSymbol::Fe);
+        assert!(matches!(
+            LcaoStates::for_configuration(&fe, 8, 1),
+            Err(LcaoError::TooFewStates { required: 2, .. })
+        ));
+        let table = crate::element::ElementTable::builtin()
+            .with_override_str(
+                "test",
+                "Element_info:\n  - symbol: Fe\n    valence_charge: 7\n",
+            )
+            .unwrap();
+        assert_eq!(
+            table.get_by_symbol(ElementSymbol::Fe).valence_charge(),
+            Some(7)
+        );
+        assert!(matches!(
+            LcaoStates::for_element(&table, ElementSymbol::Fe),
+            Err(LcaoError::Configuration(_))
+        ));
+    }
+}
@@ -1,8 +1,27 @@
+mod codegen;
+mod configuration;
 mod element_info;
 mod element_symbol;
+mod element_table;
+mod lcao;
+mod otfg;
+mod potential_set;
+mod species;
 mod yaml_parser;
 mod yaml_to_rs;
 
-pub use element_info::{Conventions, Element, LookupElement};
-pub use element_symbol::{ElementFamily, ElementSymbol};
+pub use configuration::{ConfigurationError, ElectronConfiguration, Shell};
+pub use element_info::{
+    BasisPrecision, Classification, Conventions, Cutoffs, Element, Functional, LookupElement,
+    OtfgLibrary, Potential, PotentialError, PotentialFile, PotentialFormat, PotentialKind,
+};
+pub use element_symbol::{Block, ElementCategory, ElementFamily, ElementSymbol, SymbolError};
+pub use element_table::{ElementField, ElementTable, FieldSource, TableError, BUILTIN_LAYER};
+pub use lcao::{LcaoError, LcaoStates};
+pub use otfg::{
+    AngularMomentum, Occupation, OtfgDefinition, OtfgError, OtfgNumber, OtfgOption, Projector,
+};
+pub use potential_set::{MixedFunctionals, PotentialSet, SetPotential, UnknownPotentialSet};
+pub use species::{Species, SpeciesError};
+pub use yaml_parser::{ElementOverride, ElementOverrideTable};
 pub use yaml_to_rs::ElementYamlTable;
@@ -0,0 +1,662 @@
+//! Parser and writer for CASTEP on-the-fly generation (OTFG) pseudopotential
+//! definitions, e.g. `2|1.4|10|12|13|20:21(qc=6)` or the core-hole variant
+//! `2|1.4|10|12|13|20:21(qc=6){1s1.00}`.
+//!
+//! The layout is
+//! `local|rc|[inner radii|...]coarse|medium|fine|projectors(options)[reference]{overrides}`,
+//! where the three energies are the suggested cutoffs in Hartree.
+
+use std::{fmt::Display, str::FromStr};
+
+use super::{ElectronConfiguration, ElementSymbol, LookupElement};
+
+/// A number as written in an OTFG string. The number of decimals is kept so
+/// that `1.40` is written back as `1.40`. Only plain decimals such as `6`
+/// or `-1.40` are accepted, as other forms (`1.4e2`, `01.4`, `.5`) would
+/// not be written back as given.
+#[derive(Debug, Clone, Copy)]
+pub struct OtfgNumber {
+    value: f64,
+    decimals: u8,
+}
+
+impl OtfgNumber {
+    pub fn new(value: f64, decimals: u8) -> Self {
+        Self { value, decimals }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+impl From<f64> for OtfgNumber {
+    fn from(value: f64) -> Self {
+        let decimals = format!("{}", value)
+            .split_once('.')
+            .map_or(0, |(_int, frac)| frac.len() as u8);
+        Self { value, decimals }
+    }
+}
+
+impl From<u32> for OtfgNumber {
+    fn from(value: u32) -> Self {
+        Self {
+            value: value.into(),
+            decimals: 0,
+        }
+    }
+}
+
+impl PartialEq for OtfgNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.value.to_bits() == other.value.to_bits() && self.decimals == other.decimals
+    }
+}
+
+impl Eq for OtfgNumber {}
+
+impl std::hash::Hash for OtfgNumber {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.to_bits().hash(state);
+        self.decimals.hash(state);
+    }
+}
+
+impl Display for OtfgNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.*}", self.decimals as usize, self.value)
+    }
+}
+
+impl FromStr for OtfgNumber {
+    type Err = OtfgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || OtfgError::InvalidNumber(s.to_string());
+        let unsigned = s.strip_prefix('-').unwrap_or(s);
+        let (int, frac) = match unsigned.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (unsigned, None),
+        };
+        let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        let leading_zero = int.len() > 1 && int.starts_with('0');
+        if !is_digits(int) || leading_zero || !frac.is_none_or(is_digits) {
+            return Err(invalid());
+        }
+        let decimals = frac.map_or(Ok(0), |frac| {
+            u8::try_from(frac.len()).map_err(|_| invalid())
+        })?;
+        let value = s.parse::<f64>().map_err(|_| invalid())?;
+        Ok(Self { value, decimals })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AngularMomentum {
+    S,
+    P,
+    D,
+    F,
+}
+
+impl AngularMomentum {
+    /// The quantum number `l`.
+    pub fn l(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn from_l(l: u8) -> Option<Self> {
+        match l {
+            0 => Some(AngularMomentum::S),
+            1 => Some(AngularMomentum::P),
+            2 => Some(AngularMomentum::D),
+            3 => Some(AngularMomentum::F),
+            _ => None,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            AngularMomentum::S => 's',
+            AngularMomentum::P => 'p',
+            AngularMomentum::D => 'd',
+            AngularMomentum::F => 'f',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter.to_ascii_lowercase() {
+            's' => Some(AngularMomentum::S),
+            'p' => Some(AngularMomentum::P),
+            'd' => Some(AngularMomentum::D),
+            'f' => Some(AngularMomentum::F),
+            _ => None,
+        }
+    }
+}
+
+/// A beta projector such as `20`, `31UU` or `32LGG`: principal quantum number,
+/// angular momentum, then the generation flags CASTEP attaches to it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Projector {
+    pub n: u8,
+    pub l: AngularMomentum,
+    pub flags: String,
+}
+
+impl Display for Projector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.n, self.l.l(), self.flags)
+    }
+}
+
+impl FromStr for Projector {
+    type Err = OtfgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || OtfgError::InvalidProjector(s.to_string());
+        let mut chars = s.chars();
+        let n = chars
+            .next()
+            .and_then(|c| c.to_digit(10))
+            .ok_or_else(invalid)? as u8;
+        let l = chars
+            .next()
+            .and_then(|c| c.to_digit(10))
+            .and_then(|l| AngularMomentum::from_l(l as u8))
+            .ok_or_else(invalid)?;
+        let flags = chars.as_str();
+        if !flags.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            n,
+            l,
+            flags: flags.to_string(),
+        })
+    }
+}
+
+/// Options in parentheses after the projectors, e.g. `qc=6`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OtfgOption {
+    /// `qc`, the cutoff wavevector of the pseudisation.
+    Qc(OtfgNumber),
+    Other {
+        key: String,
+        value: Option<String>,
+    },
+}
+
+impl Display for OtfgOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OtfgOption::Qc(qc) => write!(f, "qc={qc}"),
+            OtfgOption::Other { key, value: None } => f.write_str(key),
+            OtfgOption::Other {
+                key,
+                value: Some(value),
+            } => write!(f, "{key}={value}"),
+        }
+    }
+}
+
+impl FromStr for OtfgOption {
+    type Err = OtfgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((key, value)) if key.trim().eq_ignore_ascii_case("qc") => {
+                Ok(OtfgOption::Qc(OtfgNumber::from_str(value.trim())?))
+            }
+            Some((key, value)) => Ok(OtfgOption::Other {
+                key: key.trim().to_string(),
+                value: Some(value.trim().to_string()),
+            }),
+            None => Ok(OtfgOption::Other {
+                key: s.trim().to_string(),
+                value: None,
+            }),
+        }
+    }
+}
+
+/// An orbital occupation such as `1s1.00` or `3d6`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Occupation {
+    pub n: u8,
+    pub l: AngularMomentum,
+    pub electrons: OtfgNumber,
+}
+
+impl Occupation {
+    pub fn new(n: u8, l: AngularMomentum, electrons: impl Into<OtfgNumber>) -> Self {
+        Self {
+            n,
+            l,
+            electrons: electrons.into(),
+        }
+    }
+}
+
+impl Display for Occupation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.n, self.l.letter(), self.electrons)
+    }
+}
+
+impl FromStr for Occupation {
+    type Err = OtfgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || OtfgError::InvalidOccupation(s.to_string());
+        let letter_at = s
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(invalid)?;
+        let n = s[..letter_at].parse::<u8>().map_err(|_| invalid())?;
+        let mut rest = s[letter_at..].chars();
+        let l = rest
+            .next()
+            .and_then(AngularMomentum::from_letter)
+            .ok_or_else(invalid)?;
+        let electrons = OtfgNumber::from_str(rest.as_str()).map_err(|_| invalid())?;
+        Ok(Self { n, l, electrons })
+    }
+}
+
+/// A parsed OTFG definition string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OtfgDefinition {
+    pub local_channel: u8,
+    /// Core radius in Bohr.
+    pub core_radius: OtfgNumber,
+    /// Optional radii written between the core radius and the energies.
+    pub inner_radii: Vec<OtfgNumber>,
+    /// Suggested cutoff energies in Hartree.
+    pub coarse_energy: OtfgNumber,
+    pub medium_energy: OtfgNumber,
+    pub fine_energy: OtfgNumber,
+    pub projectors: Vec<Projector>,
+    pub options: Vec<OtfgOption>,
+    /// Reference configuration in square brackets.
+    pub reference: Vec<Occupation>,
+    /// Configuration overrides in braces, used for core-hole potentials.
+    pub overrides: Vec<Occupation>,
+    /// `,` or `:` between occupations, as parsed. Both groups use the same.
+    pub occupation_separator: char,
+}
+
+impl OtfgDefinition {
+    pub fn qc(&self) -> Option<OtfgNumber> {
+        self.options.iter().find_map(|opt| match opt {
+            OtfgOption::Qc(qc) => Some(*qc),
+            _ => None,
+        })
+    }
+
+    /// Set `qc`, replacing an existing value.
+    pub fn set_qc(&mut self, qc: impl Into<OtfgNumber>) {
+        let qc = qc.into();
+        match self
+            .options
+            .iter_mut()
+            .find(|opt| matches!(opt, OtfgOption::Qc(_)))
+        {
+            Some(opt) => *opt = OtfgOption::Qc(qc),
+            None => self.options.push(OtfgOption::Qc(qc)),
+        }
+    }
+
+    /// Returns a copy with the occupation of shell `n``l` overridden, which is
+    /// how CASTEP defines core-hole potentials, e.g. `{1s1.00}`.
+    pub fn with_core_hole(
+        &self,
+        n: u8,
+        l: AngularMomentum,
+        electrons: impl Into<OtfgNumber>,
+    ) -> Self {
+        let mut definition = self.clone();
+        let occupation = Occupation::new(n, l, electrons);
+        match definition
+            .overrides
+            .iter_mut()
+            .find(|occ| occ.n == n && occ.l == l)
+        {
+            Some(occ) => *occ = occupation,
+            None => definition.overrides.push(occupation),
+        }
+        definition
+    }
+
+    /// Returns a copy with one electron removed from shell `n``l` of the
+    /// ground-state configuration of `symbol`, e.g. `{1s1.00}` for carbon.
+    pub fn with_ground_state_core_hole(
+        &self,
+        symbol: ElementSymbol,
+        n: u8,
+        l: AngularMomentum,
+    ) -> Result<Self, OtfgError> {
+        let electrons = ElectronConfiguration::ground_state(symbol).occupation(n, l);
+        if electrons == 0 {
+            return Err(OtfgError::EmptyShell { symbol, n, l });
+        }
+        Ok(self.with_core_hole(n, l, OtfgNumber::new(f64::from(electrons - 1), 2)))
+    }
+
+    /// The core-hole potential of `symbol` in `table`, whose potential must
+    /// be an OTFG definition string.
+    /// # Example
+    /// ```
+    /// use castep_periodic_table::element::{AngularMomentum, ElementSymbol, ElementTable, OtfgDefinition};
+    ///
+    /// let table = ElementTable::builtin()
+    ///     .with_override_str("otfg", "Element_info:\n  - symbol: C\n    potential: 2|1.4|10|12|13|20:21(qc=6)\n")
+    ///     .unwrap();
+    /// let hole = OtfgDefinition::core_hole(&table, ElementSymbol::C, 1, AngularMomentum::S).unwrap();
+    /// assert_eq!(hole.to_string(), "2|1.4|10|12|13|20:21(qc=6){1s1.00}");
+    /// ```
+    pub fn core_hole<T: LookupElement>(
+        table: &T,
+        symbol: ElementSymbol,
+        n: u8,
+        l: AngularMomentum,
+    ) -> Result<Self, OtfgError> {
+        table
+            .get_by_symbol(symbol)
+            .potential()
+            .as_otfg()
+            .ok_or(OtfgError::NotOnTheFly(symbol))?
+            .with_ground_state_core_hole(symbol, n, l)
+    }
+}
+
+fn write_joined<T: Display>(
+    f: &mut std::fmt::Formatter<'_>,
+    items: &[T],
+    separator: impl Display,
+) -> std::fmt::Result {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{separator}")?;
+        }
+        write!(f, "{item}")?;
+    }
+    Ok(())
+}
+
+impl Display for OtfgDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}|{}|", self.local_channel, self.core_radius)?;
+        for radius in self.inner_radii.iter() {
+            write!(f, "{radius}|")?;
+        }
+        write!(
+            f,
+            "{}|{}|{}|",
+            self.coarse_energy, self.medium_energy, self.fine_energy
+        )?;
+        write_joined(f, &self.projectors, ":")?;
+        if !self.options.is_empty() {
+            f.write_str("(")?;
+            write_joined(f, &self.options, ",")?;
+            f.write_str(")")?;
+        }
+        if !self.reference.is_empty() {
+            f.write_str("[")?;
+            write_joined(f, &self.reference, self.occupation_separator)?;
+            f.write_str("]")?;
+        }
+        if !self.overrides.is_empty() {
+            f.write_str("{")?;
+            write_joined(f, &self.overrides, self.occupation_separator)?;
+            f.write_str("}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Takes the content of a bracketed group at the start of `s`, returning the
+/// content and the remaining input.
+fn take_group(s: &str, open: char, close: char) -> Result<Option<(&str, &str)>, OtfgError> {
+    match s.strip_prefix(open) {
+        Some(rest) => {
+            let end = rest.find(close).ok_or(OtfgError::Unclosed(open))?;
+            Ok(Some((&rest[..end], &rest[end + 1..])))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Parses occupations separated by `,` or `:`, recording the separator in
+/// `separator`. Mixing separators is an error, as it could not be written
+/// back.
+fn parse_occupations(s: &str, separator: &mut Option<char>) -> Result<Vec<Occupation>, OtfgError> {
+    for found in s.chars().filter(|&c| c == ',' || c == ':') {
+        match separator {
+            Some(expected) if *expected != found => {
+                return Err(OtfgError::MixedSeparators(s.to_string()))
+            }
+            _ => *separator = Some(found),
+        }
+    }
+    s.split([',', ':'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(Occupation::from_str)
+        .collect()
+}
+
+impl FromStr for OtfgDefinition {
+    type Err = OtfgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields: Vec<&str> = s.trim().split('|').collect();
+        let tail = fields.pop().ok_or(OtfgError::MissingField("projectors"))?;
+        if fields.len() < 5 {
+            return Err(OtfgError::MissingField(
+                ["local channel", "core radius", "coarse", "medium", "fine"][fields.len()],
+            ));
+        }
+        let local_channel = fields[0]
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| OtfgError::InvalidNumber(fields[0].to_string()))?;
+        let mut numbers = fields[1..]
+            .iter()
+            .map(|field| OtfgNumber::from_str(field.trim()))
+            .collect::<Result<Vec<OtfgNumber>, OtfgError>>()?;
+        let fine_energy = numbers.pop().expect("checked length");
+        let medium_energy = numbers.pop().expect("checked length");
+        let coarse_energy = numbers.pop().expect("checked length");
+        let core_radius = numbers.remove(0);
+        let inner_radii = numbers;
+
+        let projector_end = tail.find(['(', '[', '{']).unwrap_or(tail.len());
+        let projectors = tail[..projector_end]
+            .split(':')
+            .map(|p| Projector::from_str(p.trim()))
+            .collect::<Result<Vec<Projector>, OtfgError>>()?;
+        let mut rest = &tail[projector_end..];
+        let mut options = Vec::new();
+        if let Some((group, remaining)) = take_group(rest, '(', ')')? {
+            options = group
+                .split(',')
+                .filter(|opt| !opt.trim().is_empty())
+                .map(OtfgOption::from_str)
+                .collect::<Result<Vec<OtfgOption>, OtfgError>>()?;
+            rest = remaining;
+        }
+        let mut separator = None;
+        let mut reference = Vec::new();
+        if let Some((group, remaining)) = take_group(rest, '[', ']')? {
+            reference = parse_occupations(group, &mut separator)?;
+            rest = remaining;
+        }
+        let mut overrides = Vec::new();
+        if let Some((group, remaining)) = take_group(rest, '{', '}')? {
+            overrides = parse_occupations(group, &mut separator)?;
+            rest = remaining;
+        }
+        if !rest.trim().is_empty() {
+            return Err(OtfgError::TrailingCharacters(rest.to_string()));
+        }
+        Ok(Self {
+            local_channel,
+            core_radius,
+            inner_radii,
+            coarse_energy,
+            medium_energy,
+            fine_energy,
+            projectors,
+            options,
+            reference,
+            overrides,
+            occupation_separator: separator.unwrap_or(','),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum OtfgError {
+    MissingField(&'static str),
+    InvalidNumber(String),
+    InvalidProjector(String),
+    InvalidOccupation(String),
+    Unclosed(char),
+    TrailingCharacters(String),
+    /// Occupations separated by both `,` and `:`.
+    MixedSeparators(String),
+    /// The potential of the element is not an OTFG definition string.
+    NotOnTheFly(ElementSymbol),
+    /// The shell is empty in the ground state of the element.
+    EmptyShell {
+        symbol: ElementSymbol,
+        n: u8,
+        l: AngularMomentum,
+    },
+}
+
+impl Display for OtfgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OtfgError::MissingField(field) => write!(f, "missing OTFG field: {field}"),
+            OtfgError::InvalidNumber(s) => write!(f, "invalid number in OTFG string: {s}"),
+            OtfgError::InvalidProjector(s) => write!(f, "invalid projector: {s}"),
+            OtfgError::InvalidOccupation(s) => write!(f, "invalid orbital occupation: {s}"),
+            OtfgError::Unclosed(c) => write!(f, "unclosed `{c}` in OTFG string"),
+            OtfgError::TrailingCharacters(s) => write!(f, "trailing characters: {s}"),
+            OtfgError::MixedSeparators(s) => {
+                write!(f, "occupations separated by both `,` and `:`: {s}")
+            }
+            OtfgError::NotOnTheFly(symbol) => {
+                write!(f, "the potential of {symbol} is not an OTFG definition")
+            }
+            OtfgError::EmptyShell { symbol, n, l } => {
+                write!(f, "{symbol} has no {n}{} electrons", l.letter())
+            }
+        }
+    }
+}
+
+impl std::error::Error for OtfgError {}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{ElementSymbol, ElementTable},
+    };
+
+    use super::{AngularMomentum, OtfgDefinition, OtfgError, OtfgNumber};
+
+    #[test]
+    fn test_otfg_round_trip() {
+        let inputs = [
+            "2|1.4|10|12|13|20:21(qc=6)",
+            "2|1.4|10|12|13|20:21(qc=6){1s1.00}",
+            "3|2.1|7|8|9|40U:41UU:32U:43UU(qc=4.5)",
+            "1|0.6|13|15|17|10(qc=8)",
+            "3|2.0|2.0|1.3|7|10|12|30U:40:31:32(qc=5.5)[3d6.0,4s2.0]",
+            // The separator between occupations is kept.
+            "3|2.0|7|8|9|30U:40:31:32(qc=5.5)[3d6:4s2]",
+            "3|2.0|7|8|9|30U:40:31:32(qc=5.5)[3d6:4s2]{2p5.00}",
+            "2|1.4|10|12|13|20:21(qc=-0.50)",
+        ];
+        for input in inputs {
+            let definition = OtfgDefinition::from_str(input).unwrap();
+            assert_eq!(definition.to_string(), input);
+        }
+        let carbon = OtfgDefinition::from_str("2|1.4|10|12|13|20:21(qc=6)").unwrap();
+        assert_eq!(carbon.local_channel, 2);
+        assert_eq!(carbon.core_radius.value(), 1.4);
+        assert_eq!(carbon.fine_energy.value(), 13.0);
+        assert_eq!(carbon.projectors.len(), 2);
+        assert_eq!(carbon.projectors[1].l, AngularMomentum::P);
+        assert_eq!(carbon.qc().unwrap().value(), 6.0);
+        let core_hole = carbon.with_core_hole(1, AngularMomentum::S, OtfgNumber::new(1.0, 2));
+        assert_eq!(core_hole.to_string(), "2|1.4|10|12|13|20:21(qc=6){1s1.00}");
+        let mut tweaked = carbon.clone();
+        tweaked.fine_energy = 15.into();
+        tweaked.set_qc(7.5);
+        assert_eq!(tweaked.to_string(), "2|1.4|10|12|15|20:21(qc=7.5)");
+    }
+
+    #[test]
+    fn test_otfg_errors() {
+        assert!(OtfgDefinition::from_str("2|1.4|10|12").is_err());
+        assert!(OtfgDefinition::from_str("2|1.4|10|12|13|2x:21").is_err());
+        assert!(OtfgDefinition::from_str("2|1.4|10|12|13|20:21(qc=6").is_err());
+        assert!(OtfgDefinition::from_str("2|1.4|10|12|13|20:21(qc=6)extra").is_err());
+        // Numbers that would not be written back as given.
+        for number in ["1.4e2", "1.4E2", "01.4", ".5", "1.", "+1.4", "inf", "NaN"] {
+            let input = format!("2|{number}|10|12|13|20:21(qc=6)");
+            assert!(
+                matches!(
+                    OtfgDefinition::from_str(&input),
+                    Err(OtfgError::InvalidNumber(_))
+                ),
+                "{input}"
+            );
+        }
+        assert!(matches!(
+            OtfgDefinition::from_str("3|2.0|7|8|9|30U:40(qc=5.5)[3d6:4s2,4p0]"),
+            Err(OtfgError::MixedSeparators(_))
+        ));
+        assert!(matches!(
+            OtfgDefinition::from_str("3|2.0|7|8|9|30U:40(qc=5.5)[3d6:4s2]{2p5,3s1}"),
+            Err(OtfgError::MixedSeparators(_))
+        ));
+    }
+
+    #[test]
+    fn test_core_hole_from_element() {
+        let table = ElementTable::builtin()
+            .with_override_str(
+                "otfg",
+                "Element_info:\n  - symbol: Fe\n    potential: 3|2.0|7|8|9|30U:40:31:32(qc=5.5)\n",
+            )
+            .unwrap();
+        let fe_2p =
+            OtfgDefinition::core_hole(&table, ElementSymbol::Fe, 2, AngularMomentum::P).unwrap();
+        assert_eq!(
+            fe_2p.to_string(),
+            "3|2.0|7|8|9|30U:40:31:32(qc=5.5){2p5.00}"
+        );
+        assert!(matches!(
+            OtfgDefinition::core_hole(&table, ElementSymbol::Fe, 4, AngularMomentum::P),
+            Err(OtfgError::EmptyShell { n: 4, .. })
+        ));
+        // The built-in table names potential files.
+        assert!(matches!(
+            OtfgDefinition::core_hole(&ELEMENT_TABLE, ElementSymbol::Fe, 1, AngularMomentum::S),
+            Err(OtfgError::NotOnTheFly(ElementSymbol::Fe))
+        ));
+    }
+}
@@ -0,0 +1,416 @@
+use std::{borrow::Cow, collections::BTreeMap, fmt::Display, str::FromStr, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::data::ELEMENT_TABLE;
+
+use super::{
+    element_info::{Classification, Cutoffs, Functional, OtfgLibrary, Potential},
+    element_table::BUILTIN_LAYER,
+    ElementSymbol,
+};
+
+const POTENTIAL_SETS_YAML: &str = include_str!("../../potential_sets.yaml");
+
+/// The potential a set gives an element, with the values that belong to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetPotential {
+    pub potential: Potential,
+    /// `None` when the set does not give the value.
+    pub lcao: Option<u8>,
+    pub valence_charge: Option<u8>,
+    pub cutoffs: Option<Cutoffs>,
+}
+
+impl SetPotential {
+    /// A potential whose other values are not known.
+    pub fn new(potential: Potential) -> Self {
+        Self {
+            potential,
+            lcao: None,
+            valence_charge: None,
+            cutoffs: None,
+        }
+    }
+}
+
+/// A named choice of potential for the elements.
+///
+/// There is no PBEsol set on purpose: CASTEP ships no PBEsol potential
+/// files to name. For PBEsol, use an OTFG library such as `C19`, whose
+/// potentials are generated with the `XC_FUNCTIONAL` of the calculation.
+/// # Example
+/// ```
+/// use castep_periodic_table::element::{ElementSymbol, ElementTable, LookupElement, PotentialSet};
+///
+/// // The built-in table mixes LDA potentials (e.g. `He_00.usp`) with PBE ones.
+/// let builtin = PotentialSet::builtin();
+/// let warning = builtin
+///     .mixed_functionals_for(&[ElementSymbol::He, ElementSymbol::C])
+///     .unwrap();
+/// assert_eq!(warning.to_string(), "potentials mix functionals: LDA (He), PBE (C)");
+///
+/// let set: PotentialSet = "00PBE".parse().unwrap();
+/// assert!(set.mixed_functionals().is_none());
+/// assert!(set.get(ElementSymbol::He).is_none());
+/// let table = ElementTable::builtin().with_potential_set(&set);
+/// let fe = table.get_by_symbol(ElementSymbol::Fe);
+/// assert_eq!(fe.potential().to_string(), "Fe_00PBE.uspcc");
+/// assert_eq!(fe.valence_charge(), Some(8));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PotentialSet {
+    name: Cow<'static, str>,
+    /// One entry per element, ordered by atomic number. `None` where the
+    /// set has no potential.
+    potentials: Vec<Option<SetPotential>>,
+}
+
+impl PotentialSet {
+    /// The potentials of `ELEMENT_TABLE`.
+    pub fn builtin() -> Self {
+        Self::from_table(BUILTIN_LAYER, &ELEMENT_TABLE)
+    }
+
+    /// The potentials of an element table, with their values in the table.
+    pub fn from_table<T: Classification>(name: impl Into<Cow<'static, str>>, table: &T) -> Self {
+        Self {
+            name: name.into(),
+            potentials: table
+                .elements()
+                .iter()
+                .map(|elm| {
+                    Some(SetPotential {
+                        potential: elm.potential().clone(),
+                        lcao: elm.lcao(),
+                        valence_charge: elm.valence_charge(),
+                        cutoffs: elm.cutoffs(),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// A set built by calling `potential` for every element.
+    pub fn from_fn<F>(name: impl Into<Cow<'static, str>>, potential: F) -> Self
+    where
+        F: Fn(ElementSymbol) -> Option<SetPotential>,
+    {
+        Self {
+            name: name.into(),
+            potentials: ELEMENT_TABLE
+                .iter()
+                .map(|elm| potential(elm.symbol()))
+                .collect(),
+        }
+    }
+
+    /// A set of `potential_sets.yaml`: `00PBE` or `00` (LDA) ultrasoft
+    /// files.
+    pub fn named(name: &str) -> Option<Self> {
+        named_sets().iter().find(|set| set.name == name).cloned()
+    }
+
+    /// A CASTEP OTFG library for every element. The valence charges,
+    /// cutoffs and LCAO states of the generated potentials are not known.
+    pub fn library(library: OtfgLibrary) -> Self {
+        Self::from_fn(library.to_string(), |_| {
+            Some(SetPotential::new(Potential::Library(library)))
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get(&self, symbol: ElementSymbol) -> Option<&SetPotential> {
+        self.potentials[symbol.table_index()].as_ref()
+    }
+
+    pub fn potentials(&self) -> &[Option<SetPotential>] {
+        &self.potentials
+    }
+
+    /// The elements with a potential in the set.
+    pub fn iter(&self) -> impl Iterator<Item = (ElementSymbol, &SetPotential)> {
+        ELEMENT_TABLE
+            .iter()
+            .map(|elm| elm.symbol())
+            .zip(self.potentials.iter())
+            .filter_map(|(symbol, entry)| entry.as_ref().map(|entry| (symbol, entry)))
+    }
+
+    /// The elements without a potential in the set.
+    pub fn missing(&self) -> impl Iterator<Item = ElementSymbol> + '_ {
+        ELEMENT_TABLE
+            .iter()
+            .map(|elm| elm.symbol())
+            .zip(self.potentials.iter())
+            .filter(|(_, entry)| entry.is_none())
+            .map(|(symbol, _)| symbol)
+    }
+
+    /// Warns when the potential files of the set use more than one
+    /// functional.
+    pub fn mixed_functionals(&self) -> Option<MixedFunctionals> {
+        MixedFunctionals::check(
+            self.iter()
+                .map(|(symbol, entry)| (symbol, &entry.potential)),
+        )
+    }
+
+    /// Like `mixed_functionals`, for the species of one calculation.
+    pub fn mixed_functionals_for(&self, species: &[ElementSymbol]) -> Option<MixedFunctionals> {
+        MixedFunctionals::check(
+            species
+                .iter()
+                .filter_map(|symbol| self.get(*symbol).map(|entry| (*symbol, &entry.potential))),
+        )
+    }
+}
+
+/// Names accepted: `built-in`, an OTFG library such as `C19` or `NCP19`,
+/// or a set of `potential_sets.yaml` such as `00PBE`.
+impl FromStr for PotentialSet {
+    type Err = UnknownPotentialSet;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == BUILTIN_LAYER {
+            return Ok(Self::builtin());
+        }
+        if let Ok(library) = OtfgLibrary::from_str(s) {
+            return Ok(Self::library(library));
+        }
+        Self::named(s).ok_or_else(|| UnknownPotentialSet(s.to_string()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetEntryYaml {
+    symbol: ElementSymbol,
+    potential: Option<String>,
+    #[serde(rename = "LCAO")]
+    lcao: Option<u8>,
+    valence_charge: Option<u8>,
+    cutoffs: Option<Cutoffs>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetYaml {
+    name: String,
+    potentials: Vec<SetEntryYaml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetsYaml {
+    #[serde(rename = "Potential_sets")]
+    sets: Vec<SetYaml>,
+}
+
+/// The sets of `potential_sets.yaml`.
+fn named_sets() -> &'static [PotentialSet] {
+    static SETS: OnceLock<Vec<PotentialSet>> = OnceLock::new();
+    SETS.get_or_init(|| {
+        let parsed: SetsYaml =
+            serde_yaml::from_str(POTENTIAL_SETS_YAML).expect("potential_sets.yaml is valid");
+        parsed
+            .sets
+            .into_iter()
+            .map(|set| {
+                let symbols = set.potentials.iter().map(|entry| entry.symbol);
+                assert!(
+                    symbols.eq(ELEMENT_TABLE.iter().map(|elm| elm.symbol())),
+                    "set {} of potential_sets.yaml lists every element in order",
+                    set.name
+                );
+                let potentials = set
+                    .potentials
+                    .into_iter()
+                    .map(|entry| {
+                        entry.potential.map(|potential| SetPotential {
+                            potential: potential
+                                .parse()
+                                .expect("potential_sets.yaml has valid potentials"),
+                            lcao: entry.lcao,
+                            valence_charge: entry.valence_charge,
+                            cutoffs: entry.cutoffs,
+                        })
+                    })
+                    .collect();
+                PotentialSet {
+                    name: Cow::Owned(set.name),
+                    potentials,
+                }
+            })
+            .collect()
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPotentialSet(pub String);
+
+impl Display for UnknownPotentialSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown potential set `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownPotentialSet {}
+
+/// Potential files generated with different functionals. On-the-fly
+/// potentials and files without a recognised functional are not counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MixedFunctionals {
+    pub groups: BTreeMap<Functional, Vec<ElementSymbol>>,
+}
+
+impl MixedFunctionals {
+    /// `None` when at most one functional is used.
+    pub fn check<'a, I>(potentials: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (ElementSymbol, &'a Potential)>,
+    {
+        let mut groups: BTreeMap<Functional, Vec<ElementSymbol>> = BTreeMap::new();
+        for (symbol, potential) in potentials {
+            if let Some(functional) = potential.functional() {
+                groups.entry(functional).or_default().push(symbol);
+            }
+        }
+        (groups.len() > 1).then_some(Self { groups })
+    }
+}
+
+impl Display for MixedFunctionals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let groups: Vec<String> = self
+            .groups
+            .iter()
+            .map(|(functional, symbols)| {
+                let symbols: Vec<String> = symbols.iter().map(|s| s.to_string()).collect();
+                format!("{functional} ({})", symbols.join(", "))
+            })
+            .collect();
+        write!(f, "potentials mix functionals: {}", groups.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{
+            ElementField, ElementSymbol, ElementTable, Functional, LookupElement, OtfgLibrary,
+            Potential,
+        },
+    };
+
+    use super::PotentialSet;
+
+    #[test]
+    fn test_builtin_mixes_functionals() {
+        let warning = PotentialSet::builtin().mixed_functionals().unwrap();
+        assert!(warning.groups.contains_key(&Functional::Pbe));
+        let lda = &warning.groups[&Functional::Lda];
+        assert!(lda.contains(&ElementSymbol::He));
+        assert!(lda.contains(&ElementSymbol::Xe));
+        assert!(lda.contains(&ElementSymbol::Tm));
+        assert!(!lda.contains(&ElementSymbol::C));
+        assert!(PotentialSet::builtin()
+            .mixed_functionals_for(&[ElementSymbol::C, ElementSymbol::Fe])
+            .is_none());
+    }
+
+    #[test]
+    fn test_named_sets() {
+        let set: PotentialSet = "00PBE".parse().unwrap();
+        assert_eq!(set.name(), "00PBE");
+        let fe = set.get(ElementSymbol::Fe).unwrap();
+        assert_eq!(fe.potential.to_string(), "Fe_00PBE.uspcc");
+        assert_eq!(fe.valence_charge, Some(8));
+        assert_eq!(fe.lcao, Some(3));
+        assert!(set.get(ElementSymbol::He).is_none());
+        assert!(set.get(ElementSymbol::Tm).is_none());
+        assert!(set.missing().any(|symbol| symbol == ElementSymbol::Xe));
+        assert!(set.mixed_functionals().is_none());
+        let lda: PotentialSet = "00".parse().unwrap();
+        assert!(lda
+            .iter()
+            .all(|(_, entry)| entry.potential.functional() == Some(Functional::Lda)));
+        assert_eq!(
+            lda.get(ElementSymbol::Tm).unwrap().potential.to_string(),
+            "Tm_00.usp"
+        );
+        assert!(lda.get(ElementSymbol::C).is_none());
+        let c19: PotentialSet = "c19".parse().unwrap();
+        let u = c19.get(ElementSymbol::U).unwrap();
+        assert_eq!(u.potential, Potential::Library(OtfgLibrary::C19));
+        assert_eq!((u.lcao, u.valence_charge, u.cutoffs), (None, None, None));
+        assert!(c19.mixed_functionals().is_none());
+        assert_eq!(
+            "NCP19".parse::<PotentialSet>().unwrap(),
+            PotentialSet::library(OtfgLibrary::Ncp19)
+        );
+        assert_eq!(
+            "built-in".parse::<PotentialSet>().unwrap(),
+            PotentialSet::builtin()
+        );
+        for unknown in ["PBE", "00XYZ", "01PBE"] {
+            assert!(unknown.parse::<PotentialSet>().is_err(), "{unknown}");
+        }
+    }
+
+    #[test]
+    fn test_table_with_set() {
+        let table =
+            ElementTable::builtin().with_potential_set(&PotentialSet::library(OtfgLibrary::C19));
+        let h = table.get_by_symbol(ElementSymbol::H);
+        assert_eq!(h.potential(), &Potential::Library(OtfgLibrary::C19));
+        // The values of `H_00PBE.usp` do not carry over to the library.
+        assert_eq!(h.valence_charge(), None);
+        assert_eq!(h.cutoffs(), None);
+        assert_eq!(h.lcao(), None);
+        for field in [
+            ElementField::Potential,
+            ElementField::Lcao,
+            ElementField::ValenceCharge,
+            ElementField::Cutoffs,
+        ] {
+            assert_eq!(table.source_of(ElementSymbol::H, field), "C19");
+        }
+        assert_eq!(
+            PotentialSet::from_table("C19", &table),
+            PotentialSet::library(OtfgLibrary::C19)
+        );
+
+        let table = ElementTable::builtin()
+            .with_override_str(
+                "hard",
+                "Element_info:\n  - symbol: He\n    potential: He_C19.usp\n    valence_charge: 4\n",
+            )
+            .unwrap()
+            .with_potential_set(&"00".parse().unwrap());
+        let he = table.get_by_symbol(ElementSymbol::He);
+        assert_eq!(he.potential().to_string(), "He_00.usp");
+        assert_eq!(he.valence_charge(), Some(2));
+        assert_eq!(he.cutoffs(), ELEMENT_TABLE[1].cutoffs());
+        assert_eq!(
+            table.source_of(ElementSymbol::He, ElementField::ValenceCharge),
+            "00"
+        );
+        // Fe has no LDA potential in the set and keeps its own.
+        assert_eq!(
+            table
+                .get_by_symbol(ElementSymbol::Fe)
+                .potential()
+                .to_string(),
+            "Fe_00PBE.uspcc"
+        );
+        assert_eq!(
+            table.source_of(ElementSymbol::Fe, ElementField::Potential),
+            "built-in"
+        );
+    }
+}
@@ -0,0 +1,371 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::isotope::{Isotope, IsotopeError, IsotopeMix};
+
+use super::{
+    element_info::Cutoffs, ConfigurationError, ElectronConfiguration, Element, ElementSymbol,
+    LcaoError, LcaoStates, LookupElement, Potential,
+};
+
+/// A CASTEP species: a label such as `Fe1` or `Fe:up` naming an element,
+/// with its own values for any `Element` field. Fields that are not set
+/// fall back to the element in the table.
+///
+/// Species are compared and ordered by element, then label.
+/// # Example
+/// ```
+/// use castep_periodic_table::data::ELEMENT_TABLE;
+/// use castep_periodic_table::element::{ElementSymbol, Species};
+///
+/// let down: Species = "Fe:down".parse().unwrap();
+/// let down = down.with_spin(0);
+/// assert_eq!(down.element(), ElementSymbol::Fe);
+/// assert_eq!(down.spin(&ELEMENT_TABLE), 0);
+/// assert_eq!(down.mass(&ELEMENT_TABLE), 55.8470001221);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Species {
+    label: String,
+    element: ElementSymbol,
+    lcao: Option<u8>,
+    lcao_states: Option<LcaoStates>,
+    mass: Option<f64>,
+    potential: Option<Potential>,
+    valence_charge: Option<u8>,
+    cutoffs: Option<Cutoffs>,
+    spin: Option<u8>,
+    covalent_radius: Option<f64>,
+}
+
+impl Species {
+    fn with_label(label: String, element: ElementSymbol) -> Self {
+        Self {
+            label,
+            element,
+            lcao: None,
+            lcao_states: None,
+            mass: None,
+            potential: None,
+            valence_charge: None,
+            cutoffs: None,
+            spin: None,
+            covalent_radius: None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn element(&self) -> ElementSymbol {
+        self.element
+    }
+
+    /// The label has no number or tag after the element symbol.
+    pub fn is_plain_element(&self) -> bool {
+        self.label == self.element.to_string()
+    }
+
+    pub fn with_lcao(mut self, lcao: u8) -> Self {
+        self.lcao = Some(lcao);
+        self.lcao_states = None;
+        self
+    }
+
+    /// LCAO states by channel. Also sets `lcao` to their total.
+    pub fn with_lcao_states(mut self, states: LcaoStates) -> Self {
+        self.lcao = Some(states.total());
+        self.lcao_states = Some(states);
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f64) -> Self {
+        self.mass = Some(mass);
+        self
+    }
+
+    /// Use the mass of an isotope of the species' element.
+    pub fn with_isotope(self, isotope: &Isotope) -> Result<Self, IsotopeError> {
+        self.check_isotope_element(isotope.symbol)?;
+        Ok(self.with_mass(isotope.mass))
+    }
+
+    /// Use the mean mass of a mixture of isotopes of the species' element.
+    pub fn with_isotope_mix(self, mix: &IsotopeMix) -> Result<Self, IsotopeError> {
+        self.check_isotope_element(mix.symbol())?;
+        Ok(self.with_mass(mix.mass()))
+    }
+
+    fn check_isotope_element(&self, isotope: ElementSymbol) -> Result<(), IsotopeError> {
+        if isotope == self.element {
+            Ok(())
+        } else {
+            Err(IsotopeError::ElementMismatch {
+                species: self.element,
+                isotope,
+            })
+        }
+    }
+
+    pub fn with_potential(mut self, potential: Potential) -> Self {
+        self.potential = Some(potential);
+        self
+    }
+
+    pub fn with_valence_charge(mut self, valence_charge: u8) -> Self {
+        self.valence_charge = Some(valence_charge);
+        self
+    }
+
+    pub fn with_cutoffs(mut self, cutoffs: Cutoffs) -> Self {
+        self.cutoffs = Some(cutoffs);
+        self
+    }
+
+    pub fn with_spin(mut self, spin: u8) -> Self {
+        self.spin = Some(spin);
+        self
+    }
+
+    pub fn with_covalent_radius(mut self, radius: f64) -> Self {
+        self.covalent_radius = Some(radius);
+        self
+    }
+
+    /// The element of `table` with the values set on this species.
+    pub fn resolve<T: LookupElement>(&self, table: &T) -> Element {
+        let mut element = table.get_by_symbol(self.element).clone();
+        if let Some(lcao) = self.lcao {
+            element.lcao = Some(lcao);
+        }
+        if let Some(mass) = self.mass {
+            element.mass = mass;
+        }
+        if let Some(potential) = self.potential.as_ref() {
+            element.potential = potential.clone();
+        }
+        if let Some(valence_charge) = self.valence_charge {
+            element.valence_charge = Some(valence_charge);
+        }
+        if let Some(cutoffs) = self.cutoffs {
+            element.cutoffs = Some(cutoffs);
+        }
+        if let Some(spin) = self.spin {
+            element.spin = spin;
+        }
+        if let Some(radius) = self.covalent_radius {
+            element.covalent_radius = Some(radius);
+        }
+        element
+    }
+
+    pub fn lcao<T: LookupElement>(&self, table: &T) -> Option<u8> {
+        self.lcao
+            .or_else(|| table.get_by_symbol(self.element).lcao())
+    }
+
+    /// LCAO states by channel, checked against the valence shells for the
+    /// species' valence charge. Without states set, the states are derived
+    /// from `lcao`.
+    pub fn lcao_states<T: LookupElement>(&self, table: &T) -> Result<LcaoStates, LcaoError> {
+        let configuration = ElectronConfiguration::ground_state(self.element);
+        let valence_charge = self.valence_charge(table).ok_or(LcaoError::Configuration(
+            ConfigurationError::UnknownValenceCharge(self.element),
+        ));
+        let states = valence_charge.and_then(|valence_charge| match self.lcao_states {
+            Some(states) => configuration
+                .valence(valence_charge)
+                .map_err(LcaoError::Configuration)
+                .and_then(|valence| states.check(&valence))
+                .map(|_| states),
+            None => self
+                .lcao(table)
+                .ok_or(LcaoError::UnknownStates(self.element))
+                .and_then(|total| {
+                    LcaoStates::for_configuration(&configuration, valence_charge, total)
+                }),
+        });
+        states.map_err(|error| LcaoError::Species {
+            label: self.label.clone(),
+            error: Box::new(error),
+        })
+    }
+
+    pub fn mass<T: LookupElement>(&self, table: &T) -> f64 {
+        self.mass
+            .unwrap_or_else(|| table.get_by_symbol(self.element).mass())
+    }
+
+    pub fn potential<'a, T: LookupElement>(&'a self, table: &'a T) -> &'a Potential {
+        self.potential
+            .as_ref()
+            .unwrap_or_else(|| table.get_by_symbol(self.element).potential())
+    }
+
+    pub fn valence_charge<T: LookupElement>(&self, table: &T) -> Option<u8> {
+        self.valence_charge
+            .or_else(|| table.get_by_symbol(self.element).valence_charge())
+    }
+
+    pub fn cutoffs<T: LookupElement>(&self, table: &T) -> Option<Cutoffs> {
+        self.cutoffs
+            .or_else(|| table.get_by_symbol(self.element).cutoffs())
+    }
+
+    pub fn spin<T: LookupElement>(&self, table: &T) -> u8 {
+        self.spin
+            .unwrap_or_else(|| table.get_by_symbol(self.element).spin())
+    }
+
+    pub fn covalent_radius<T: LookupElement>(&self, table: &T) -> Option<f64> {
+        self.covalent_radius
+            .or_else(|| table.get_by_symbol(self.element).covalent_radius())
+    }
+}
+
+impl From<ElementSymbol> for Species {
+    fn from(symbol: ElementSymbol) -> Self {
+        Self::with_label(symbol.to_string(), symbol)
+    }
+}
+
+/// Labels are an element symbol, in any case, followed by an optional
+/// number and an optional `:tag`, e.g. `Fe`, `Fe1`, `Fe:up` or `O2:core`.
+impl FromStr for Species {
+    type Err = SpeciesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let label = s.trim();
+        let invalid = || SpeciesError::InvalidLabel(label.to_string());
+        let letters = label
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(label.len());
+        // Prefer the two-letter symbol, so that `Co1` is cobalt.
+        let (element, rest) = (1..=letters.min(2))
+            .rev()
+            .find_map(|len| symbol_prefix(&label[..len]).map(|symbol| (symbol, &label[len..])))
+            .ok_or_else(invalid)?;
+        let (number, tag) = match rest.split_once(':') {
+            Some((number, tag)) => (number, Some(tag)),
+            None => (rest, None),
+        };
+        let valid_tag = tag.is_none_or(|tag| {
+            !tag.is_empty() && tag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if !number.chars().all(|c| c.is_ascii_digit()) || !valid_tag {
+            return Err(invalid());
+        }
+        let canonical = format!("{}{}", element, &label[element.to_string().len()..]);
+        Ok(Self::with_label(canonical, element))
+    }
+}
+
+/// Element symbol written in any case.
+fn symbol_prefix(letters: &str) -> Option<ElementSymbol> {
+    let mut chars = letters.chars();
+    let first = chars.next()?.to_ascii_uppercase();
+    let symbol: String = std::iter::once(first)
+        .chain(chars.map(|c| c.to_ascii_lowercase()))
+        .collect();
+    ElementSymbol::from_str(&symbol).ok()
+}
+
+impl Display for Species {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(&self.label)
+    }
+}
+
+impl Eq for Species {}
+
+impl PartialEq for Species {
+    fn eq(&self, other: &Self) -> bool {
+        self.element == other.element && self.label == other.label
+    }
+}
+
+impl Ord for Species {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.element, &self.label).cmp(&(other.element, &other.label))
+    }
+}
+
+impl PartialOrd for Species {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpeciesError {
+    InvalidLabel(String),
+}
+
+impl Display for SpeciesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpeciesError::InvalidLabel(label) => write!(
+                f,
+                "invalid species label `{label}`, expected an element symbol with an optional number and `:tag`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpeciesError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{ElementSymbol, LookupElement, OtfgLibrary, Potential},
+    };
+
+    use super::Species;
+
+    #[test]
+    fn test_parse_labels() {
+        let cases = [
+            ("Fe", ElementSymbol::Fe, "Fe"),
+            ("Fe1", ElementSymbol::Fe, "Fe1"),
+            ("Fe:up", ElementSymbol::Fe, "Fe:up"),
+            ("O2:core_hole", ElementSymbol::O, "O2:core_hole"),
+            ("Co1", ElementSymbol::Co, "Co1"),
+            ("C1", ElementSymbol::C, "C1"),
+            ("fe2", ElementSymbol::Fe, "Fe2"),
+            ("NI:a", ElementSymbol::Ni, "Ni:a"),
+        ];
+        for (input, element, label) in cases {
+            let species: Species = input.parse().unwrap();
+            assert_eq!(species.element(), element, "{input}");
+            assert_eq!(species.label(), label, "{input}");
+        }
+        for input in ["", "1", "Xx", "Fe-1", "Fe:", "Fe:up down", "Fex", "Fe1a"] {
+            assert!(input.parse::<Species>().is_err(), "{input}");
+        }
+        assert!(Species::from(ElementSymbol::Fe).is_plain_element());
+        assert!(!"Fe1".parse::<Species>().unwrap().is_plain_element());
+    }
+
+    #[test]
+    fn test_fallback() {
+        let hole = "C:exc"
+            .parse::<Species>()
+            .unwrap()
+            .with_potential(Potential::Library(OtfgLibrary::C19))
+            .with_mass(13.0);
+        let carbon = ELEMENT_TABLE.get_by_symbol(ElementSymbol::C);
+        assert_eq!(hole.mass(&ELEMENT_TABLE), 13.0);
+        assert_eq!(hole.lcao(&ELEMENT_TABLE), carbon.lcao());
+        assert_eq!(
+            hole.potential(&ELEMENT_TABLE),
+            &Potential::Library(OtfgLibrary::C19)
+        );
+        let resolved = hole.resolve(&ELEMENT_TABLE);
+        assert_eq!(resolved.symbol(), ElementSymbol::C);
+        assert_eq!(resolved.mass(), 13.0);
+        assert_eq!(resolved.spin(), carbon.spin());
+        assert!("C1".parse::<Species>().unwrap() < "C:exc".parse::<Species>().unwrap());
+        assert!("C:exc".parse::<Species>().unwrap() < "O".parse::<Species>().unwrap());
+    }
+}
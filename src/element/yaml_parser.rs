@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::element_symbol::ElementSymbol;
+use super::{element_info::Cutoffs, element_symbol::ElementSymbol};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ElementYAML {
@@ -10,6 +10,8 @@ pub struct ElementYAML {
     pub lcao: u8,
     pub mass: f64,
     pub potential: String,
+    pub valence_charge: Option<u8>,
+    pub cutoffs: Option<Cutoffs>,
     pub spin: u8,
     pub covalent_radius: Option<f64>,
 }
@@ -33,3 +35,25 @@ impl PartialOrd for ElementYAML {
         Some(self.cmp(other))
     }
 }
+
+/// A partial entry of an override file. Only the given fields replace the
+/// values of the element with the same symbol.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ElementOverride {
+    pub symbol: ElementSymbol,
+    #[serde(rename = "LCAO")]
+    pub lcao: Option<u8>,
+    pub mass: Option<f64>,
+    pub potential: Option<String>,
+    pub valence_charge: Option<u8>,
+    pub cutoffs: Option<Cutoffs>,
+    pub spin: Option<u8>,
+    pub covalent_radius: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ElementOverrideTable {
+    #[serde(rename = "Element_info")]
+    pub elements: Vec<ElementOverride>,
+}
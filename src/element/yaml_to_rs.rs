@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use super::yaml_parser::ElementYAML;
+use super::{
+    codegen::{self, RawCutoffs, RawElement},
+    yaml_parser::ElementYAML,
+};
 
 #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ElementYamlTable {
@@ -12,6 +15,7 @@ impl ElementYamlTable {
     /// Helper function to write the type annotation for an array in rust.
     /// # Arguments:
     /// - type_annotation - type of the array item
+    ///
     /// The size is determined internally with `self.elements.len()`
     /// # Returns:
     /// `"[{type_annotation}; size]"`
@@ -37,14 +41,35 @@ impl ElementYamlTable {
     pub fn new_const_array(var_name: &str, var_type: &str, array_content: &str) -> String {
         format!("pub const {var_name}: {var_type} = [{array_content}];")
     }
+    /// Helper function to write a file-based `Potential` as a constant rust
+    /// expression.
+    /// # Panics
+    /// Only pseudopotential files can be stored in the constant table.
+    pub fn new_potential_expr(potential: &str) -> String {
+        codegen::potential_expr(potential).unwrap_or_else(|e| panic!("{e}"))
+    }
+    /// The `ELEMENT_TABLE` constant, identical to what `build.rs` generates
+    /// into `data.rs`.
     pub fn export_struct(&self) -> String {
-        let init_element = |elm: &ElementYAML| -> String {
-            format!("Element{{ symbol: ElementSymbol::{:?}, atomic_number: {}_u8, lcao: {}_u8, mass: {:?}, potential: \"{}\", spin:{}_u8, covalent_radius: {}\n}}", &elm.symbol, elm.atomic_number, elm.lcao, elm.mass, &elm.potential, elm.spin, if let Some(r) = elm.covalent_radius {format!("Some({})", r)} else {"None".into()})
-            // Debug formatter is used for mass to avoid making f64 numbers like `147.0` to `147`
-        };
-        let var_name = "ELEMENT_TABLE";
-        let var_type = self.new_array_type("Element");
-        let array_content = self.new_array_content(init_element);
-        Self::new_const_array(var_name, &var_type, &array_content)
+        let elements: Vec<RawElement> = self
+            .elements
+            .iter()
+            .map(|elm| RawElement {
+                symbol: elm.symbol.to_string(),
+                atomic_number: elm.atomic_number,
+                lcao: elm.lcao,
+                mass: elm.mass,
+                potential: elm.potential.clone(),
+                valence_charge: elm.valence_charge,
+                cutoffs: elm.cutoffs.map(|cutoffs| RawCutoffs {
+                    coarse: cutoffs.coarse,
+                    medium: cutoffs.medium,
+                    fine: cutoffs.fine,
+                }),
+                spin: elm.spin,
+                covalent_radius: elm.covalent_radius,
+            })
+            .collect();
+        codegen::element_table(&elements).unwrap_or_else(|e| panic!("{e}"))
     }
 }
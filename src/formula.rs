@@ -0,0 +1,547 @@
+//! Chemical formulas and element compositions.
+//!
+//! ```
+//! use castep_periodic_table::element::ElementSymbol;
+//! use castep_periodic_table::formula::Composition;
+//!
+//! let gypsum: Composition = "CaSO4·2H2O".parse().unwrap();
+//! assert_eq!(gypsum.get(ElementSymbol::O), 6.0);
+//! assert_eq!(gypsum.hill_formula(), "CaH4O6S");
+//! ```
+//!
+//! Supported syntax: element symbols with integer or decimal counts,
+//! nested `()` and `[]` groups with multipliers, and hydrate or adduct parts
+//! separated by `·`, `•` or `*` with an optional leading multiplier. A `.` is
+//! a decimal point. Outside brackets, `CuSO4.5H2O` could also be a hydrate,
+//! so a decimal count above one followed by more elements is rejected:
+//! write `CuSO4·5H2O` for the hydrate, or bracket the count, as in
+//! `(Li1.2)Ni0.2Mn0.6O2`.
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use crate::element::{ElementSymbol, LookupElement, SymbolError};
+
+/// Tolerance used to decide whether an amount is an integer.
+const INTEGER_TOLERANCE: f64 = 1e-9;
+
+/// Grams per cubic centimetre in one amu per cubic angstrom.
+pub const AMU_PER_ANG3_TO_G_PER_CM3: f64 = 1.66053906660;
+
+/// Amount of each element, ordered by atomic number.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Composition {
+    amounts: BTreeMap<ElementSymbol, f64>,
+}
+
+impl Composition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `amount` of `symbol`.
+    pub fn add(&mut self, symbol: ElementSymbol, amount: f64) {
+        *self.amounts.entry(symbol).or_insert(0.0) += amount;
+    }
+
+    /// Amount of `symbol`, zero if absent.
+    pub fn get(&self, symbol: ElementSymbol) -> f64 {
+        self.amounts.get(&symbol).copied().unwrap_or(0.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ElementSymbol, f64)> + '_ {
+        self.amounts
+            .iter()
+            .map(|(&symbol, &amount)| (symbol, amount))
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = ElementSymbol> + '_ {
+        self.amounts.keys().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.amounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amounts.is_empty()
+    }
+
+    pub fn total_atoms(&self) -> f64 {
+        self.amounts.values().sum()
+    }
+
+    /// Multiply every amount by `factor`.
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            amounts: self
+                .amounts
+                .iter()
+                .map(|(&symbol, &amount)| (symbol, amount * factor))
+                .collect(),
+        }
+    }
+
+    /// Amounts rescaled to sum to one.
+    pub fn normalized(&self) -> Self {
+        let total = self.total_atoms();
+        if total == 0.0 {
+            return self.clone();
+        }
+        self.scaled(1.0 / total)
+    }
+
+    pub fn is_integral(&self) -> bool {
+        self.amounts
+            .values()
+            .all(|amount| (amount - amount.round()).abs() < INTEGER_TOLERANCE)
+    }
+
+    /// Smallest integer formula unit, e.g. `Fe4O6` to `Fe2O3`. Compositions
+    /// with fractional amounts are returned unchanged.
+    pub fn reduced(&self) -> Self {
+        if !self.is_integral() || self.is_empty() {
+            return self.clone();
+        }
+        let divisor = self
+            .amounts
+            .values()
+            .map(|amount| amount.round() as u64)
+            .fold(0, gcd);
+        if divisor <= 1 {
+            return self.clone();
+        }
+        self.scaled(1.0 / divisor as f64)
+    }
+
+    /// Formula in Hill order: carbon, then hydrogen, then the other elements
+    /// alphabetically. Without carbon every element is alphabetical.
+    pub fn hill_formula(&self) -> String {
+        let mut symbols: Vec<(String, f64)> = self
+            .amounts
+            .iter()
+            .map(|(symbol, &amount)| (symbol.to_string(), amount))
+            .collect();
+        let has_carbon = self.amounts.contains_key(&ElementSymbol::C);
+        symbols.sort_by(|(a, _), (b, _)| {
+            let rank = |s: &str| match (has_carbon, s) {
+                (true, "C") => 0,
+                (true, "H") => 1,
+                _ => 2,
+            };
+            rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+        });
+        symbols
+            .iter()
+            .map(|(symbol, amount)| format!("{}{}", symbol, format_amount(*amount)))
+            .collect()
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Omits a count of one and trims trailing zeros of decimal counts.
+fn format_amount(amount: f64) -> String {
+    if (amount - 1.0).abs() < INTEGER_TOLERANCE {
+        String::new()
+    } else if (amount - amount.round()).abs() < INTEGER_TOLERANCE {
+        format!("{}", amount.round() as u64)
+    } else {
+        let text = format!("{:.6}", amount);
+        text.trim_end_matches('0').to_string()
+    }
+}
+
+impl Display for Composition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.hill_formula())
+    }
+}
+
+impl FromIterator<(ElementSymbol, f64)> for Composition {
+    fn from_iter<T: IntoIterator<Item = (ElementSymbol, f64)>>(iter: T) -> Self {
+        let mut composition = Composition::new();
+        iter.into_iter()
+            .for_each(|(symbol, amount)| composition.add(symbol, amount));
+        composition
+    }
+}
+
+/// Counts each symbol of a structure's species list once per atom.
+impl FromIterator<ElementSymbol> for Composition {
+    fn from_iter<T: IntoIterator<Item = ElementSymbol>>(iter: T) -> Self {
+        iter.into_iter().map(|symbol| (symbol, 1.0)).collect()
+    }
+}
+
+impl FromStr for Composition {
+    type Err = FormulaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.is_empty() {
+            return Err(FormulaError::Empty);
+        }
+        let mut parser = Parser {
+            chars,
+            pos: 0,
+            depth: 0,
+        };
+        let mut composition = Composition::new();
+        loop {
+            let multiplier = parser.number()?.unwrap_or(1.0);
+            let part = parser.group_content()?;
+            composition.extend_scaled(&part, multiplier);
+            match parser.peek() {
+                None => break,
+                Some('·' | '•' | '*') => parser.pos += 1,
+                Some(')' | ']') => return Err(FormulaError::UnmatchedBracket(parser.pos)),
+                Some(c) => return Err(FormulaError::UnexpectedCharacter(c, parser.pos)),
+            }
+        }
+        Ok(composition)
+    }
+}
+
+/// Mass-based quantities, using the masses of `table` so that they agree with
+/// the `SPECIES_MASS` CASTEP is given.
+/// ```
+/// use castep_periodic_table::data::ELEMENT_TABLE;
+/// use castep_periodic_table::formula::Composition;
+///
+/// let rutile: Composition = "TiO2".parse().unwrap();
+/// let mass = rutile.formula_mass(&ELEMENT_TABLE);
+/// assert!((mass - 79.8980007171).abs() < 1e-8);
+/// // Two formula units in a 62.43 A^3 cell
+/// let density = rutile.density(&ELEMENT_TABLE, 62.43, 2.0);
+/// assert!((density.g_per_cm3() - 4.25).abs() < 0.01);
+/// ```
+impl Composition {
+    /// Mass of one formula unit in amu, equal to the molar mass in g/mol.
+    pub fn formula_mass<T: LookupElement>(&self, table: &T) -> f64 {
+        self.element_masses(table).values().sum()
+    }
+
+    /// Mass contributed by each element to one formula unit, in amu.
+    pub fn element_masses<T: LookupElement>(&self, table: &T) -> BTreeMap<ElementSymbol, f64> {
+        self.iter()
+            .map(|(symbol, amount)| (symbol, amount * table.get_by_symbol(symbol).mass()))
+            .collect()
+    }
+
+    pub fn mass_fractions<T: LookupElement>(&self, table: &T) -> BTreeMap<ElementSymbol, f64> {
+        let masses = self.element_masses(table);
+        let total: f64 = masses.values().sum();
+        masses
+            .into_iter()
+            .map(|(symbol, mass)| (symbol, mass / total))
+            .collect()
+    }
+
+    pub fn atomic_fractions(&self) -> BTreeMap<ElementSymbol, f64> {
+        self.normalized().amounts
+    }
+
+    /// Density of a cell of `volume` cubic angstroms holding `formula_units`
+    /// formula units.
+    pub fn density<T: LookupElement>(&self, table: &T, volume: f64, formula_units: f64) -> Density {
+        Density {
+            amu_per_ang3: self.formula_mass(table) * formula_units / volume,
+        }
+    }
+}
+
+/// A density, reported in both units that CASTEP prints.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Density {
+    amu_per_ang3: f64,
+}
+
+impl Density {
+    pub fn amu_per_ang3(&self) -> f64 {
+        self.amu_per_ang3
+    }
+
+    pub fn g_per_cm3(&self) -> f64 {
+        self.amu_per_ang3 * AMU_PER_ANG3_TO_G_PER_CM3
+    }
+}
+
+impl Display for Density {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:.6} amu/A**3 = {:.6} g/cm^3",
+            self.amu_per_ang3,
+            self.g_per_cm3()
+        )
+    }
+}
+
+impl Composition {
+    fn extend_scaled(&mut self, other: &Composition, factor: f64) {
+        other
+            .iter()
+            .for_each(|(symbol, amount)| self.add(symbol, amount * factor));
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Number of open brackets.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// An optional integer or decimal number.
+    fn number(&mut self) -> Result<Option<f64>, FormulaError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Ok(None);
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse::<f64>()
+            .map(Some)
+            .map_err(|_| FormulaError::InvalidNumber(text, start))
+    }
+
+    /// The count after an element or a group. At the top level, `4.5H2O`
+    /// may be a count of 4.5 or a count of 4 and a hydrate separator.
+    fn count(&mut self) -> Result<Option<f64>, FormulaError> {
+        let start = self.pos;
+        let count = self.number()?;
+        let text: String = self.chars[start..self.pos].iter().collect();
+        let followed_by_formula =
+            matches!(self.peek(), Some(c) if c.is_ascii_uppercase() || c == '(' || c == '[');
+        if let Some((integer, fraction)) = text.split_once('.') {
+            let above_one = integer.parse::<u64>().is_ok_and(|integer| integer > 0);
+            if self.depth == 0 && above_one && !fraction.is_empty() && followed_by_formula {
+                return Err(FormulaError::AmbiguousDot(start + integer.len()));
+            }
+        }
+        Ok(count)
+    }
+
+    /// Elements and bracketed groups until a separator, a closing bracket or
+    /// the end of input.
+    fn group_content(&mut self) -> Result<Composition, FormulaError> {
+        let mut composition = Composition::new();
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(c) if c.is_ascii_uppercase() => {
+                    let symbol = self.symbol()?;
+                    let count = self.count()?.unwrap_or(1.0);
+                    composition.add(symbol, count);
+                }
+                Some(open @ ('(' | '[')) => {
+                    let open_pos = self.pos;
+                    self.pos += 1;
+                    self.depth += 1;
+                    let inner = self.group_content()?;
+                    self.depth -= 1;
+                    let close = if open == '(' { ')' } else { ']' };
+                    if self.peek() != Some(close) {
+                        return Err(FormulaError::UnclosedBracket(open_pos));
+                    }
+                    self.pos += 1;
+                    let count = self.count()?.unwrap_or(1.0);
+                    composition.extend_scaled(&inner, count);
+                }
+                _ => break,
+            }
+        }
+        if start == self.pos {
+            return Err(match self.peek() {
+                Some(c) => FormulaError::UnexpectedCharacter(c, self.pos),
+                None => FormulaError::Empty,
+            });
+        }
+        Ok(composition)
+    }
+
+    fn symbol(&mut self) -> Result<ElementSymbol, FormulaError> {
+        let start = self.pos;
+        self.pos += 1;
+        while matches!(self.peek(), Some(c) if c.is_ascii_lowercase()) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        ElementSymbol::from_str(&text).map_err(|error| FormulaError::UnknownSymbol {
+            symbol: text,
+            position: start,
+            error,
+        })
+    }
+}
+
+/// Errors from parsing a formula. Positions count characters after
+/// whitespace is removed.
+#[derive(Debug)]
+pub enum FormulaError {
+    Empty,
+    UnknownSymbol {
+        symbol: String,
+        position: usize,
+        error: SymbolError,
+    },
+    InvalidNumber(String, usize),
+    UnexpectedCharacter(char, usize),
+    UnclosedBracket(usize),
+    UnmatchedBracket(usize),
+    /// A `.` that may be a decimal point or a hydrate separator.
+    AmbiguousDot(usize),
+}
+
+impl Display for FormulaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormulaError::Empty => f.write_str("empty formula"),
+            FormulaError::UnknownSymbol {
+                symbol, position, ..
+            } => write!(f, "unknown element symbol `{symbol}` at {position}"),
+            FormulaError::InvalidNumber(text, position) => {
+                write!(f, "invalid number `{text}` at {position}")
+            }
+            FormulaError::UnexpectedCharacter(c, position) => {
+                write!(f, "unexpected character `{c}` at {position}")
+            }
+            FormulaError::UnclosedBracket(position) => {
+                write!(f, "bracket at {position} is not closed")
+            }
+            FormulaError::UnmatchedBracket(position) => {
+                write!(f, "closing bracket at {position} has no opening bracket")
+            }
+            FormulaError::AmbiguousDot(position) => write!(
+                f,
+                "`.` at {position} may be a decimal point or a hydrate separator; \
+                 use `·` for hydrates or bracket the decimal count"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FormulaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FormulaError::UnknownSymbol { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{ElementSymbol, ElementTable},
+    };
+
+    use super::{Composition, FormulaError};
+
+    #[test]
+    fn test_parse_formula() {
+        let hematite = Composition::from_str("Fe2O3").unwrap();
+        assert_eq!(hematite.get(ElementSymbol::Fe), 2.0);
+        assert_eq!(hematite.get(ElementSymbol::O), 3.0);
+        let portlandite = Composition::from_str("Ca(OH)2").unwrap();
+        assert_eq!(portlandite.get(ElementSymbol::H), 2.0);
+        assert_eq!(portlandite.get(ElementSymbol::O), 2.0);
+        let vitriol = Composition::from_str("CuSO4·5H2O").unwrap();
+        assert_eq!(vitriol.get(ElementSymbol::O), 9.0);
+        assert_eq!(vitriol.get(ElementSymbol::H), 10.0);
+        assert_eq!(vitriol, Composition::from_str("CuSO4*5H2O").unwrap());
+        let lsmo = Composition::from_str("La0.7Sr0.3MnO3").unwrap();
+        assert!((lsmo.get(ElementSymbol::La) - 0.7).abs() < 1e-12);
+        assert_eq!(lsmo.total_atoms(), 5.0);
+        let nested = Composition::from_str("K4[Fe(CN)6]").unwrap();
+        assert_eq!(nested.get(ElementSymbol::C), 6.0);
+        assert_eq!(nested.get(ElementSymbol::N), 6.0);
+        assert_eq!(Composition::from_str(" H2 O ").unwrap().len(), 2);
+        // Decimal counts that cannot be hydrate separators.
+        let ybco = Composition::from_str("YBa2Cu3O6.5").unwrap();
+        assert_eq!(ybco.get(ElementSymbol::O), 6.5);
+        let nmc = Composition::from_str("(Li1.2)Ni0.2Mn0.6O2").unwrap();
+        assert!((nmc.get(ElementSymbol::Li) - 1.2).abs() < 1e-12);
+        let hemihydrate = Composition::from_str("CaSO4·0.5H2O").unwrap();
+        assert_eq!(hemihydrate.get(ElementSymbol::H), 1.0);
+    }
+
+    #[test]
+    fn test_hydrate_dot() {
+        for input in ["CuSO4.5H2O", "Li1.2Ni0.2Mn0.6O2", "Ca(OH)2.5H2O"] {
+            assert!(
+                matches!(
+                    Composition::from_str(input),
+                    Err(FormulaError::AmbiguousDot(_))
+                ),
+                "{input}"
+            );
+        }
+        assert!(matches!(
+            Composition::from_str("CuSO4.5H2O"),
+            Err(FormulaError::AmbiguousDot(5))
+        ));
+    }
+
+    #[test]
+    fn test_formula_errors() {
+        for input in ["", "Xx2", "Fe2O3)", "Ca(OH2", "fe2", "Fe2O3·", "Fe..2"] {
+            assert!(Composition::from_str(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_representations() {
+        let composition = Composition::from_str("Fe4O6").unwrap();
+        assert_eq!(composition.reduced().hill_formula(), "Fe2O3");
+        let normalized = composition.normalized();
+        assert!((normalized.get(ElementSymbol::O) - 0.6).abs() < 1e-12);
+        let ethanol = Composition::from_str("CH3CH2OH").unwrap();
+        assert_eq!(ethanol.to_string(), "C2H6O");
+        let water = Composition::from_str("H2O").unwrap();
+        assert_eq!(water.hill_formula(), "H2O");
+        let salt = Composition::from_str("NaCl").unwrap();
+        assert_eq!(salt.hill_formula(), "ClNa");
+        let lsmo = Composition::from_str("La0.7Sr0.3MnO3").unwrap();
+        assert_eq!(lsmo.reduced(), lsmo);
+        assert_eq!(lsmo.hill_formula(), "La0.7MnO3Sr0.3");
+        let species = [ElementSymbol::Si, ElementSymbol::O, ElementSymbol::O];
+        let quartz: Composition = species.into_iter().collect();
+        assert_eq!(quartz.hill_formula(), "O2Si");
+    }
+
+    #[test]
+    fn test_masses() {
+        let water = Composition::from_str("H2O").unwrap();
+        let mass = water.formula_mass(&ELEMENT_TABLE);
+        assert!((mass - (2.0 * 1.0080000162 + 15.9989995956)).abs() < 1e-12);
+        let fractions = water.mass_fractions(&ELEMENT_TABLE);
+        assert!((fractions.values().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((fractions[&ElementSymbol::O] - 15.9989995956 / mass).abs() < 1e-12);
+        let atomic = water.atomic_fractions();
+        assert!((atomic[&ElementSymbol::H] - 2.0 / 3.0).abs() < 1e-12);
+        let heavy = ElementTable::builtin()
+            .with_override_str("D2O", "Element_info:\n  - symbol: H\n    mass: 2.014\n")
+            .unwrap();
+        assert!((water.formula_mass(&heavy) - (2.0 * 2.014 + 15.9989995956)).abs() < 1e-12);
+        let density = water.density(&ELEMENT_TABLE, 30.0, 1.0);
+        assert!((density.amu_per_ang3() - mass / 30.0).abs() < 1e-12);
+        assert!((density.g_per_cm3() - mass / 30.0 * 1.6605390666).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,463 @@
+//! Default Hubbard U and J values for DFT+U, and the CASTEP `HUBBARD_U`
+//! block.
+//!
+//! The defaults are in `hubbard_u.yaml`, next to `element_table.yaml`.
+//! Parameters can also be given to one species, e.g. `Fe1` but not `Fe2`.
+//!
+//! ```
+//! use castep_periodic_table::element::{AngularMomentum, ElementSymbol, Species};
+//! use castep_periodic_table::hubbard::{HubbardBlock, HubbardTable};
+//!
+//! let table = HubbardTable::builtin().with_parameter(ElementSymbol::Ni, AngularMomentum::D, 6.4, 0.0, "project");
+//! let block = HubbardBlock::new(&table, [ElementSymbol::O, ElementSymbol::Fe, ElementSymbol::Ni]);
+//! assert_eq!(
+//!     block.to_string(),
+//!     "%BLOCK HUBBARD_U\neV\n      Fe d: 5.3000\n      Ni d: 6.4000\n%ENDBLOCK HUBBARD_U\n"
+//! );
+//!
+//! let fe1: Species = "Fe1".parse().unwrap();
+//! let fe2: Species = "Fe2".parse().unwrap();
+//! let table = table.with_species_parameter(&fe1, AngularMomentum::D, 4.0, 0.0, "project");
+//! let block = HubbardBlock::with_species(&table, [fe1, fe2]);
+//! assert_eq!(
+//!     block.to_string(),
+//!     "%BLOCK HUBBARD_U\neV\n     Fe1 d: 4.0000\n     Fe2 d: 5.3000\n%ENDBLOCK HUBBARD_U\n"
+//! );
+//! ```
+
+use std::{
+    borrow::Borrow, collections::BTreeSet, fmt::Display, path::Path, str::FromStr, sync::OnceLock,
+};
+
+use serde::Deserialize;
+
+use crate::element::{AngularMomentum, ElementSymbol, Species};
+
+const HUBBARD_YAML: &str = include_str!("../hubbard_u.yaml");
+
+const HARTREE_IN_EV: f64 = 27.211386245988;
+
+/// U and J of one orbital channel of an element, in eV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HubbardParameter {
+    pub symbol: ElementSymbol,
+    pub channel: AngularMomentum,
+    pub u: f64,
+    pub j: f64,
+    /// Literature tag, see `hubbard_u.yaml`.
+    pub source: String,
+    /// Label of the only species the parameter applies to, e.g. `Fe1`.
+    /// `None` for every species of the element.
+    pub species: Option<String>,
+}
+
+impl HubbardParameter {
+    /// `U - J`, the value CASTEP uses.
+    pub fn u_eff(&self) -> f64 {
+        self.u - self.j
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HubbardEntry {
+    symbol: ElementSymbol,
+    channel: char,
+    #[serde(rename = "U")]
+    u: f64,
+    #[serde(rename = "J", default)]
+    j: f64,
+    source: String,
+    species: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HubbardYaml {
+    #[serde(rename = "Hubbard_U")]
+    entries: Vec<HubbardEntry>,
+}
+
+/// Hubbard parameters by element and channel, and by species for the
+/// species given their own values.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HubbardTable {
+    /// Ordered by element, species, then channel. Element parameters come
+    /// before the species ones.
+    parameters: Vec<HubbardParameter>,
+}
+
+impl HubbardTable {
+    /// The values of `hubbard_u.yaml`.
+    pub fn builtin() -> Self {
+        static BUILTIN: OnceLock<HubbardTable> = OnceLock::new();
+        BUILTIN
+            .get_or_init(|| {
+                HubbardTable::default()
+                    .with_override_str(HUBBARD_YAML)
+                    .expect("hubbard_u.yaml is valid")
+            })
+            .clone()
+    }
+
+    /// Add or replace the parameter of `symbol`, or of its species, and
+    /// `channel`.
+    pub fn set(&mut self, parameter: HubbardParameter) {
+        let key = |p: &HubbardParameter| (p.symbol, p.species.clone(), p.channel.l());
+        match self.parameters.binary_search_by_key(&key(&parameter), key) {
+            Ok(i) => self.parameters[i] = parameter,
+            Err(i) => self.parameters.insert(i, parameter),
+        }
+    }
+
+    pub fn with_parameter(
+        mut self,
+        symbol: ElementSymbol,
+        channel: AngularMomentum,
+        u: f64,
+        j: f64,
+        source: &str,
+    ) -> Self {
+        self.set(HubbardParameter {
+            symbol,
+            channel,
+            u,
+            j,
+            source: source.to_string(),
+            species: None,
+        });
+        self
+    }
+
+    /// A parameter for `species` only, e.g. `Fe1`, taking precedence over
+    /// the parameter of its element in the same channel.
+    pub fn with_species_parameter(
+        mut self,
+        species: &Species,
+        channel: AngularMomentum,
+        u: f64,
+        j: f64,
+        source: &str,
+    ) -> Self {
+        self.set(HubbardParameter {
+            symbol: species.element(),
+            channel,
+            u,
+            j,
+            source: source.to_string(),
+            species: Some(species.label().to_string()),
+        });
+        self
+    }
+
+    /// Apply entries written in the format of `hubbard_u.yaml`.
+    pub fn with_override_str(mut self, yaml: &str) -> Result<Self, HubbardError> {
+        let parsed: HubbardYaml = serde_yaml::from_str(yaml).map_err(HubbardError::Yaml)?;
+        for entry in parsed.entries {
+            let channel = AngularMomentum::from_letter(entry.channel).ok_or(
+                HubbardError::InvalidChannel {
+                    symbol: entry.symbol,
+                    channel: entry.channel,
+                },
+            )?;
+            if let Some(label) = entry.species.as_deref() {
+                let invalid = || HubbardError::InvalidSpecies {
+                    symbol: entry.symbol,
+                    label: label.to_string(),
+                };
+                let species = Species::from_str(label).map_err(|_| invalid())?;
+                if species.element() != entry.symbol {
+                    return Err(invalid());
+                }
+            }
+            self.set(HubbardParameter {
+                symbol: entry.symbol,
+                channel,
+                u: entry.u,
+                j: entry.j,
+                source: entry.source,
+                species: entry.species,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn with_override_file<P: AsRef<Path>>(self, path: P) -> Result<Self, HubbardError> {
+        let yaml = std::fs::read_to_string(path).map_err(HubbardError::Io)?;
+        self.with_override_str(&yaml)
+    }
+
+    pub fn parameters(&self) -> &[HubbardParameter] {
+        &self.parameters
+    }
+
+    /// Parameters of `symbol` for all its species, ordered by channel.
+    pub fn get(&self, symbol: ElementSymbol) -> impl Iterator<Item = &HubbardParameter> {
+        self.parameters
+            .iter()
+            .filter(move |parameter| parameter.symbol == symbol && parameter.species.is_none())
+    }
+
+    /// Parameters of `species`, ordered by channel: its own, and those of
+    /// its element in the other channels.
+    pub fn for_species(&self, species: &Species) -> Vec<&HubbardParameter> {
+        let own: Vec<&HubbardParameter> = self
+            .parameters
+            .iter()
+            .filter(|parameter| parameter.species.as_deref() == Some(species.label()))
+            .collect();
+        let mut parameters: Vec<&HubbardParameter> = self
+            .get(species.element())
+            .filter(|parameter| own.iter().all(|p| p.channel != parameter.channel))
+            .chain(own.iter().copied())
+            .collect();
+        parameters.sort_by_key(|parameter| parameter.channel.l());
+        parameters
+    }
+}
+
+/// Energy units accepted in the `HUBBARD_U` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HubbardUnit {
+    #[default]
+    Ev,
+    Ha,
+}
+
+impl HubbardUnit {
+    /// Convert an energy in eV, as stored in the table, to this unit.
+    pub fn convert_ev(&self, energy: f64) -> f64 {
+        match self {
+            HubbardUnit::Ev => energy,
+            HubbardUnit::Ha => energy / HARTREE_IN_EV,
+        }
+    }
+}
+
+impl Display for HubbardUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HubbardUnit::Ev => f.write_str("eV"),
+            HubbardUnit::Ha => f.write_str("Ha"),
+        }
+    }
+}
+
+/// The `HUBBARD_U` block for a set of species. Species without parameters
+/// are left out; species are deduplicated and ordered by atomic number,
+/// then label.
+#[derive(Debug, Clone)]
+pub struct HubbardBlock<'a> {
+    table: &'a HubbardTable,
+    species: Vec<Species>,
+    unit: HubbardUnit,
+    precision: usize,
+}
+
+impl<'a> HubbardBlock<'a> {
+    pub fn new<I>(table: &'a HubbardTable, symbols: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<ElementSymbol>,
+    {
+        Self::with_species(
+            table,
+            symbols
+                .into_iter()
+                .map(|symbol| Species::from(*symbol.borrow())),
+        )
+    }
+
+    /// The block for species with their own labels, e.g. `Fe1` and `Fe2`.
+    pub fn with_species<I>(table: &'a HubbardTable, species: I) -> Self
+    where
+        I: IntoIterator<Item = Species>,
+    {
+        let species: BTreeSet<Species> = species.into_iter().collect();
+        Self {
+            table,
+            species: species.into_iter().collect(),
+            unit: HubbardUnit::Ev,
+            precision: 4,
+        }
+    }
+
+    pub fn unit(mut self, unit: HubbardUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Number of decimals. Defaults to 4.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Species that get a line in the block.
+    pub fn species(&self) -> Vec<&Species> {
+        self.species
+            .iter()
+            .filter(|species| !self.table.for_species(species).is_empty())
+            .collect()
+    }
+}
+
+impl Display for HubbardBlock<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "%BLOCK HUBBARD_U")?;
+        writeln!(f, "{}", self.unit)?;
+        for species in self.species() {
+            write!(f, "{:>8}", species)?;
+            for parameter in self.table.for_species(species) {
+                write!(
+                    f,
+                    " {}: {:.*}",
+                    parameter.channel.letter(),
+                    self.precision,
+                    self.unit.convert_ev(parameter.u_eff())
+                )?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "%ENDBLOCK HUBBARD_U")
+    }
+}
+
+#[derive(Debug)]
+pub enum HubbardError {
+    Io(std::io::Error),
+    Yaml(serde_yaml::Error),
+    InvalidChannel {
+        symbol: ElementSymbol,
+        channel: char,
+    },
+    /// The species label is invalid or names another element.
+    InvalidSpecies {
+        symbol: ElementSymbol,
+        label: String,
+    },
+}
+
+impl Display for HubbardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HubbardError::Io(error) => write!(f, "{error}"),
+            HubbardError::Yaml(error) => write!(f, "{error}"),
+            HubbardError::InvalidChannel { symbol, channel } => {
+                write!(
+                    f,
+                    "{symbol}: invalid channel `{channel}`, expected s, p, d or f"
+                )
+            }
+            HubbardError::InvalidSpecies { symbol, label } => {
+                write!(f, "{symbol}: `{label}` is not a species of {symbol}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HubbardError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HubbardError::Io(error) => Some(error),
+            HubbardError::Yaml(error) => Some(error),
+            HubbardError::InvalidChannel { .. } | HubbardError::InvalidSpecies { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{AngularMomentum, Conventions, ElementSymbol, Species},
+    };
+
+    use super::{HubbardBlock, HubbardError, HubbardTable, HubbardUnit};
+
+    #[test]
+    fn test_builtin_covers_conventions() {
+        let table = HubbardTable::builtin();
+        for elm in &ELEMENT_TABLE.metals_3d()[1..8] {
+            let d = table.get(elm.symbol()).next().unwrap();
+            assert_eq!(d.channel, AngularMomentum::D);
+        }
+        // Only the lanthanides with a published value have a default.
+        let lanthanides: Vec<ElementSymbol> = ELEMENT_TABLE
+            .rare_earth_la()
+            .iter()
+            .map(|elm| elm.symbol())
+            .filter(|symbol| table.get(*symbol).next().is_some())
+            .collect();
+        assert_eq!(lanthanides, [ElementSymbol::Ce, ElementSymbol::Gd]);
+        let gd = table.get(ElementSymbol::Gd).next().unwrap();
+        assert_eq!(gd.channel, AngularMomentum::F);
+        assert!((gd.u_eff() - 6.0).abs() < 1e-12);
+        assert_eq!(table.get(ElementSymbol::Fe).next().unwrap().source, "MP");
+        assert!(table.parameters().iter().all(|p| p.source != "typical"));
+    }
+
+    #[test]
+    fn test_hubbard_block() {
+        let table = HubbardTable::builtin()
+            .with_override_str(
+                "Hubbard_U:\n  - {symbol: U, channel: d, U: 1.2, source: test}\n  - {symbol: U, channel: f, U: 2.1, J: 0.0, source: test}\n",
+            )
+            .unwrap();
+        let block = HubbardBlock::new(
+            &table,
+            [ElementSymbol::U, ElementSymbol::Gd, ElementSymbol::O],
+        )
+        .precision(2);
+        let species: Vec<String> = block.species().iter().map(|s| s.to_string()).collect();
+        assert_eq!(species, ["Gd", "U"]);
+        assert_eq!(
+            block.to_string(),
+            "%BLOCK HUBBARD_U\neV\n      Gd f: 6.00\n       U d: 1.20 f: 2.10\n%ENDBLOCK HUBBARD_U\n"
+        );
+        let ha = HubbardBlock::new(&table, [ElementSymbol::Ni]).unit(HubbardUnit::Ha);
+        assert_eq!(
+            ha.to_string(),
+            "%BLOCK HUBBARD_U\nHa\n      Ni d: 0.2278\n%ENDBLOCK HUBBARD_U\n"
+        );
+        assert!(HubbardTable::default()
+            .with_override_str("Hubbard_U:\n  - {symbol: Fe, channel: x, U: 1, source: x}\n")
+            .is_err());
+    }
+
+    #[test]
+    fn test_species_parameters() {
+        let table = HubbardTable::builtin()
+            .with_override_str(
+                "Hubbard_U:\n  - {symbol: Fe, species: \"Fe:hs\", channel: d, U: 4.0, source: test}\n  - {symbol: Fe, species: Fe2, channel: p, U: 1.0, source: test}\n",
+            )
+            .unwrap();
+        let species: Vec<Species> = ["Fe:hs", "Fe2", "Fe3", "O1"]
+            .iter()
+            .map(|label| label.parse().unwrap())
+            .collect();
+        assert_eq!(table.get(ElementSymbol::Fe).count(), 1);
+        let fe2: Vec<char> = table
+            .for_species(&species[1])
+            .iter()
+            .map(|p| p.channel.letter())
+            .collect();
+        assert_eq!(fe2, ['p', 'd']);
+        let block = HubbardBlock::with_species(&table, species).precision(1);
+        assert_eq!(
+            block.to_string(),
+            "%BLOCK HUBBARD_U\neV\n     Fe2 p: 1.0 d: 5.3\n     Fe3 d: 5.3\n   Fe:hs d: 4.0\n%ENDBLOCK HUBBARD_U\n"
+        );
+        for invalid in ["Fe", "O1", "Fe-1"] {
+            let yaml = format!(
+                "Hubbard_U:\n  - {{symbol: Ni, species: \"{invalid}\", channel: d, U: 1, source: x}}\n"
+            );
+            assert!(
+                matches!(
+                    HubbardTable::default().with_override_str(&yaml),
+                    Err(HubbardError::InvalidSpecies { .. })
+                ),
+                "{invalid}"
+            );
+        }
+    }
+}
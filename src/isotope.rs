@@ -0,0 +1,465 @@
+//! Isotope masses, natural abundances and nuclear spins, and isotope
+//! substitution in `SPECIES_MASS`.
+//!
+//! The data are in `isotopes.yaml`, next to `element_table.yaml`. Every
+//! element with a natural isotopic composition is listed; Tc, Pm, Po to Ac
+//! and the elements after U are not.
+//!
+//! ```
+//! use castep_periodic_table::cell::SpeciesBlocks;
+//! use castep_periodic_table::data::ELEMENT_TABLE;
+//! use castep_periodic_table::element::{ElementSymbol, Species};
+//! use castep_periodic_table::isotope::{parse_isotope, IsotopeMix};
+//!
+//! let deuterium = parse_isotope("D").unwrap();
+//! assert_eq!(deuterium.mass_number, 2);
+//! assert_eq!(deuterium.spin.to_string(), "1");
+//!
+//! // 95% 6Li.
+//! let li = IsotopeMix::new(ElementSymbol::Li, [(6, 0.95), (7, 0.05)]).unwrap();
+//! let species = [
+//!     deuterium.species(),
+//!     Species::from(ElementSymbol::Li).with_isotope_mix(&li).unwrap(),
+//! ];
+//! let blocks = SpeciesBlocks::with_species(&ELEMENT_TABLE, species).mass_precision(4);
+//! assert_eq!(
+//!     blocks.species_mass(),
+//!     "%BLOCK SPECIES_MASS\n     H:D            2.0141\n      Li            6.0652\n%ENDBLOCK SPECIES_MASS\n"
+//! );
+//! ```
+
+use std::{fmt::Display, str::FromStr, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::element::{ElementSymbol, Species};
+
+const ISOTOPES_YAML: &str = include_str!("../isotopes.yaml");
+
+/// Nuclear spin quantum number, an integer or half-integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NuclearSpin {
+    twice: u8,
+}
+
+impl NuclearSpin {
+    pub fn from_twice(twice: u8) -> Self {
+        Self { twice }
+    }
+
+    /// Twice the spin, e.g. 3 for `3/2`.
+    pub fn twice(&self) -> u8 {
+        self.twice
+    }
+
+    pub fn value(&self) -> f64 {
+        self.twice as f64 / 2.0
+    }
+
+    /// Nuclei with spin above 1/2 have an electric quadrupole moment.
+    pub fn is_quadrupolar(&self) -> bool {
+        self.twice > 1
+    }
+}
+
+impl Display for NuclearSpin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.twice.is_multiple_of(2) {
+            write!(f, "{}", self.twice / 2)
+        } else {
+            write!(f, "{}/2", self.twice)
+        }
+    }
+}
+
+/// Accepts `1`, `3/2` and the like.
+impl FromStr for NuclearSpin {
+    type Err = IsotopeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || IsotopeError::InvalidSpin(s.to_string());
+        match s.trim().split_once('/') {
+            Some((numerator, "2")) => {
+                let twice: u8 = numerator.trim().parse().map_err(|_| invalid())?;
+                if twice.is_multiple_of(2) {
+                    return Err(invalid());
+                }
+                Ok(Self { twice })
+            }
+            Some(_) => Err(invalid()),
+            None => {
+                let spin: u8 = s.trim().parse().map_err(|_| invalid())?;
+                spin.checked_mul(2)
+                    .map(Self::from_twice)
+                    .ok_or_else(invalid)
+            }
+        }
+    }
+}
+
+/// An isotope of an element. Masses are in u, abundances are fractions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isotope {
+    pub symbol: ElementSymbol,
+    pub mass_number: u16,
+    pub mass: f64,
+    pub abundance: f64,
+    pub spin: NuclearSpin,
+    /// Gyromagnetic ratio in 10^7 rad/(T s), for NMR-active isotopes.
+    pub gyromagnetic_ratio: Option<f64>,
+    /// Electric quadrupole moment in mb (1 mb = 10^-31 m^2), for
+    /// quadrupolar isotopes.
+    pub quadrupole_moment: Option<f64>,
+}
+
+impl Isotope {
+    pub fn is_nmr_active(&self) -> bool {
+        self.spin.twice() > 0 && self.gyromagnetic_ratio.is_some()
+    }
+
+    /// Larmor frequency in MHz in a field of `field` tesla.
+    pub fn larmor_frequency(&self, field: f64) -> Option<f64> {
+        self.gyromagnetic_ratio
+            .map(|gamma| gamma * 10.0 * field / (2.0 * std::f64::consts::PI))
+    }
+
+    /// A species for this isotope labelled `H:D`, `H:T` or e.g. `C:13`,
+    /// with the isotope mass.
+    pub fn species(&self) -> Species {
+        let tag = match (self.symbol, self.mass_number) {
+            (ElementSymbol::H, 2) => "D".to_string(),
+            (ElementSymbol::H, 3) => "T".to_string(),
+            (_, mass_number) => mass_number.to_string(),
+        };
+        format!("{}:{tag}", self.symbol)
+            .parse::<Species>()
+            .expect("isotope labels are valid species labels")
+            .with_mass(self.mass)
+    }
+}
+
+/// Written as mass number and symbol, e.g. `13C`.
+impl Display for Isotope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(&format!("{}{}", self.mass_number, self.symbol))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SpinEntry {
+    Integer(u8),
+    Text(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IsotopeEntry {
+    symbol: ElementSymbol,
+    #[serde(rename = "A")]
+    mass_number: u16,
+    mass: f64,
+    abundance: f64,
+    spin: SpinEntry,
+    #[serde(default)]
+    gamma: Option<f64>,
+    #[serde(rename = "Q", default)]
+    quadrupole_moment: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IsotopeYaml {
+    #[serde(rename = "Isotopes")]
+    entries: Vec<IsotopeEntry>,
+}
+
+/// All isotopes of `isotopes.yaml`, ordered by element, then mass number.
+pub fn all_isotopes() -> &'static [Isotope] {
+    static ISOTOPES: OnceLock<Vec<Isotope>> = OnceLock::new();
+    ISOTOPES.get_or_init(|| {
+        let parsed: IsotopeYaml =
+            serde_yaml::from_str(ISOTOPES_YAML).expect("isotopes.yaml is valid");
+        let mut isotopes: Vec<Isotope> = parsed
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let spin = match entry.spin {
+                    SpinEntry::Integer(spin) => NuclearSpin::from_twice(2 * spin),
+                    SpinEntry::Text(text) => text.parse().expect("isotopes.yaml has valid spins"),
+                };
+                Isotope {
+                    symbol: entry.symbol,
+                    mass_number: entry.mass_number,
+                    mass: entry.mass,
+                    abundance: entry.abundance,
+                    spin,
+                    gyromagnetic_ratio: entry.gamma,
+                    quadrupole_moment: entry.quadrupole_moment,
+                }
+            })
+            .collect();
+        isotopes.sort_by_key(|isotope| (isotope.symbol, isotope.mass_number));
+        isotopes
+    })
+}
+
+/// Isotopes of `symbol`, ordered by mass number. Empty for elements
+/// without data.
+pub fn isotopes(symbol: ElementSymbol) -> &'static [Isotope] {
+    let all = all_isotopes();
+    let start = all.partition_point(|isotope| isotope.symbol < symbol);
+    let end = all.partition_point(|isotope| isotope.symbol <= symbol);
+    &all[start..end]
+}
+
+pub fn isotope(symbol: ElementSymbol, mass_number: u16) -> Option<&'static Isotope> {
+    isotopes(symbol)
+        .iter()
+        .find(|isotope| isotope.mass_number == mass_number)
+}
+
+pub fn most_abundant(symbol: ElementSymbol) -> Option<&'static Isotope> {
+    isotopes(symbol)
+        .iter()
+        .max_by(|a, b| a.abundance.total_cmp(&b.abundance))
+}
+
+/// NMR-active isotopes of `symbol`, ordered by mass number.
+pub fn nmr_isotopes(symbol: ElementSymbol) -> impl Iterator<Item = &'static Isotope> {
+    isotopes(symbol)
+        .iter()
+        .filter(|isotope| isotope.is_nmr_active())
+}
+
+/// Parse `D`, `T`, or a symbol and mass number in either order, e.g.
+/// `13C`, `C13` or `2H`.
+pub fn parse_isotope(notation: &str) -> Result<&'static Isotope, IsotopeError> {
+    let s = notation.trim();
+    let invalid = || IsotopeError::InvalidNotation(notation.to_string());
+    let (symbol, mass_number) = match s {
+        "D" => (ElementSymbol::H, "2"),
+        "T" => (ElementSymbol::H, "3"),
+        _ => {
+            let (letters, digits) = if s.starts_with(|c: char| c.is_ascii_digit()) {
+                let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+                let (digits, letters) = s.split_at(split);
+                (letters, digits)
+            } else {
+                let split = s.find(|c: char| c.is_ascii_digit()).ok_or_else(invalid)?;
+                s.split_at(split)
+            };
+            if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(invalid());
+            }
+            let symbol = ElementSymbol::from_str(letters).map_err(|_| invalid())?;
+            (symbol, digits)
+        }
+    };
+    if !mass_number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let mass_number: u16 = mass_number.parse().map_err(|_| invalid())?;
+    isotope(symbol, mass_number).ok_or(IsotopeError::Unknown {
+        symbol,
+        mass_number,
+    })
+}
+
+/// A mixture of isotopes of one element, e.g. natural or enriched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsotopeMix {
+    symbol: ElementSymbol,
+    /// Fractions sum to 1.
+    fractions: Vec<(&'static Isotope, f64)>,
+}
+
+impl IsotopeMix {
+    /// The natural abundances.
+    pub fn natural(symbol: ElementSymbol) -> Result<Self, IsotopeError> {
+        Self::new(
+            symbol,
+            isotopes(symbol)
+                .iter()
+                .map(|isotope| (isotope.mass_number, isotope.abundance)),
+        )
+    }
+
+    /// A mixture of `(mass_number, fraction)` pairs. Fractions are
+    /// normalised to sum to 1.
+    pub fn new<I>(symbol: ElementSymbol, fractions: I) -> Result<Self, IsotopeError>
+    where
+        I: IntoIterator<Item = (u16, f64)>,
+    {
+        let fractions = fractions
+            .into_iter()
+            .filter(|&(_, fraction)| fraction != 0.0)
+            .map(|(mass_number, fraction)| {
+                let isotope = isotope(symbol, mass_number).ok_or(IsotopeError::Unknown {
+                    symbol,
+                    mass_number,
+                })?;
+                if fraction.is_finite() && fraction > 0.0 {
+                    Ok((isotope, fraction))
+                } else {
+                    Err(IsotopeError::InvalidAbundance { symbol, fraction })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let total: f64 = fractions.iter().map(|(_, fraction)| fraction).sum();
+        if fractions.is_empty() {
+            return Err(IsotopeError::NoData(symbol));
+        }
+        Ok(Self {
+            symbol,
+            fractions: fractions
+                .into_iter()
+                .map(|(isotope, fraction)| (isotope, fraction / total))
+                .collect(),
+        })
+    }
+
+    pub fn symbol(&self) -> ElementSymbol {
+        self.symbol
+    }
+
+    pub fn fractions(&self) -> &[(&'static Isotope, f64)] {
+        &self.fractions
+    }
+
+    /// Abundance-weighted mass in u.
+    pub fn mass(&self) -> f64 {
+        self.fractions
+            .iter()
+            .map(|(isotope, fraction)| isotope.mass * fraction)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IsotopeError {
+    InvalidNotation(String),
+    InvalidSpin(String),
+    Unknown {
+        symbol: ElementSymbol,
+        mass_number: u16,
+    },
+    /// No isotopes of the element are listed.
+    NoData(ElementSymbol),
+    InvalidAbundance {
+        symbol: ElementSymbol,
+        fraction: f64,
+    },
+    /// An isotope of one element was given to a species of another.
+    ElementMismatch {
+        species: ElementSymbol,
+        isotope: ElementSymbol,
+    },
+}
+
+impl Display for IsotopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IsotopeError::InvalidNotation(notation) => write!(
+                f,
+                "invalid isotope `{notation}`, expected e.g. `D`, `13C` or `C13`"
+            ),
+            IsotopeError::InvalidSpin(spin) => write!(f, "invalid nuclear spin `{spin}`"),
+            IsotopeError::Unknown {
+                symbol,
+                mass_number,
+            } => write!(f, "no data for isotope {mass_number}{symbol}"),
+            IsotopeError::NoData(symbol) => write!(f, "no isotope data for {symbol}"),
+            IsotopeError::InvalidAbundance { symbol, fraction } => {
+                write!(f, "{symbol}: invalid isotope fraction {fraction}")
+            }
+            IsotopeError::ElementMismatch { species, isotope } => {
+                write!(f, "cannot give a {species} species the mass of {isotope}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IsotopeError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{ElementSymbol, LookupElement},
+    };
+
+    use super::{
+        all_isotopes, isotope, isotopes, most_abundant, parse_isotope, IsotopeMix, NuclearSpin,
+    };
+
+    #[test]
+    fn test_data() {
+        let mut symbols: Vec<ElementSymbol> = all_isotopes().iter().map(|i| i.symbol).collect();
+        symbols.dedup();
+        for symbol in symbols {
+            let total: f64 = isotopes(symbol).iter().map(|i| i.abundance).sum();
+            assert!((total - 1.0).abs() < 1e-3, "{symbol}: {total}");
+            // The natural mix agrees with the table masses, some of which are older
+            // standard atomic weights (Ti: 47.90).
+            let natural = IsotopeMix::natural(symbol).unwrap().mass();
+            let standard = ELEMENT_TABLE.get_by_symbol(symbol).mass();
+            assert!((natural - standard).abs() < 0.05, "{symbol}: {natural}");
+        }
+        assert_eq!(most_abundant(ElementSymbol::Fe).unwrap().mass_number, 56);
+        assert_eq!(
+            isotope(ElementSymbol::C, 13).unwrap().spin.to_string(),
+            "1/2"
+        );
+        assert!(isotopes(ElementSymbol::Tc).is_empty());
+        assert!(IsotopeMix::natural(ElementSymbol::Tc).is_err());
+        let without: Vec<ElementSymbol> = ELEMENT_TABLE
+            .iter()
+            .map(|element| element.symbol())
+            .filter(|&symbol| isotopes(symbol).is_empty())
+            .collect();
+        let expected: Vec<ElementSymbol> = ELEMENT_TABLE
+            .iter()
+            .map(|element| element.symbol())
+            .filter(|&symbol| {
+                matches!(symbol, ElementSymbol::Tc | ElementSymbol::Pm)
+                    || (ElementSymbol::Po..=ElementSymbol::Ac).contains(&symbol)
+                    || symbol > ElementSymbol::U
+            })
+            .collect();
+        assert_eq!(without, expected);
+        assert_eq!(isotopes(ElementSymbol::Sn).len(), 10);
+    }
+
+    #[test]
+    fn test_notation() {
+        for (notation, symbol, mass_number) in [
+            ("D", ElementSymbol::H, 2),
+            ("T", ElementSymbol::H, 3),
+            ("2H", ElementSymbol::H, 2),
+            ("13C", ElementSymbol::C, 13),
+            ("C13", ElementSymbol::C, 13),
+            ("6Li", ElementSymbol::Li, 6),
+            ("Fe57", ElementSymbol::Fe, 57),
+        ] {
+            let isotope = parse_isotope(notation).unwrap();
+            assert_eq!((isotope.symbol, isotope.mass_number), (symbol, mass_number));
+        }
+        for notation in ["", "13", "C", "C-13", "13C13", "14C", "Xx3"] {
+            assert!(parse_isotope(notation).is_err(), "{notation}");
+        }
+        assert_eq!(parse_isotope("C13").unwrap().to_string(), "13C");
+        assert_eq!("5/2".parse::<NuclearSpin>().unwrap().value(), 2.5);
+        assert!("2/2".parse::<NuclearSpin>().is_err());
+    }
+
+    #[test]
+    fn test_species_mass() {
+        let c13 = parse_isotope("13C").unwrap().species();
+        assert_eq!(c13.label(), "C:13");
+        assert_eq!(c13.mass(&ELEMENT_TABLE), 13.00335483507);
+        let enriched = IsotopeMix::new(ElementSymbol::B, [(10, 1.0), (11, 1.0)]).unwrap();
+        assert!((enriched.mass() - 10.51112).abs() < 1e-5);
+        assert!(IsotopeMix::new(ElementSymbol::B, [(12, 1.0)]).is_err());
+        assert!(IsotopeMix::new(ElementSymbol::B, [(10, -1.0)]).is_err());
+    }
+}
@@ -0,0 +1,403 @@
+//! Initial magnetic moments and the `SPIN=` annotations of the
+//! `POSITIONS_FRAC` block.
+//!
+//! Moments are spin-only values in Bohr magnetons, i.e. the number of
+//! unpaired electrons of the open d or f shell of an ion, filled by Hund's
+//! rule. Spins on sites are signed, so antiferromagnetic guesses can be
+//! written.
+//!
+//! ```
+//! use castep_periodic_table::data::ELEMENT_TABLE;
+//! use castep_periodic_table::element::{ElementSymbol, Species};
+//! use castep_periodic_table::magnetism::{MomentModel, PositionsFrac, Site, SpinState};
+//!
+//! let model = MomentModel::new()
+//!     .ion(ElementSymbol::Ni, 2, SpinState::High)
+//!     .ion(ElementSymbol::O, -2, SpinState::High);
+//! let sites = vec![
+//!     Site::new(ElementSymbol::Ni, [0.0, 0.0, 0.0]),
+//!     Site::new(ElementSymbol::Ni, [0.5, 0.5, 0.5]),
+//!     Site::new(ElementSymbol::O, [0.25, 0.25, 0.25]),
+//!     Site::new(ElementSymbol::O, [0.75, 0.75, 0.75]),
+//! ];
+//! // Alternate the sign of the Ni moments.
+//! let block = PositionsFrac::new(sites)
+//!     .assign_moments(&ELEMENT_TABLE, &model, |i, _| if i == 0 { 1.0 } else { -1.0 })
+//!     .unwrap();
+//! assert_eq!(block.total_spin(), 0.0);
+//! assert!(block.to_string().contains("SPIN=-2.0000"));
+//!
+//! // Fe2+ and Fe3+ of magnetite as two species.
+//! let fe2: Species = "Fe2".parse().unwrap();
+//! let fe3: Species = "Fe3".parse().unwrap();
+//! let model = MomentModel::new()
+//!     .species_ion(&fe2, 2, SpinState::High)
+//!     .species_ion(&fe3, 3, SpinState::High);
+//! let block = PositionsFrac::new(vec![Site::new(fe2, [0.0; 3]), Site::new(fe3, [0.5; 3])])
+//!     .precision(1)
+//!     .assign_moments(&ELEMENT_TABLE, &model, |_, _| 1.0)
+//!     .unwrap();
+//! assert_eq!(
+//!     block.to_string(),
+//!     "%BLOCK POSITIONS_FRAC\n     Fe2   0.0   0.0   0.0 SPIN=4.0000\n     Fe3   0.5   0.5   0.5 SPIN=5.0000\n%ENDBLOCK POSITIONS_FRAC\n"
+//! );
+//! ```
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::element::{
+    AngularMomentum, Block, ElementCategory, ElementSymbol, LookupElement, Species,
+};
+
+/// High or low spin filling of a d shell in an octahedral field. f shells
+/// are always filled high spin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SpinState {
+    #[default]
+    High,
+    Low,
+}
+
+/// The open shell of an ion: its angular momentum and number of electrons.
+/// `None` for elements whose magnetism does not come from a d or f shell.
+pub fn open_shell(
+    symbol: ElementSymbol,
+    oxidation_state: i8,
+) -> Result<Option<(AngularMomentum, u8)>, MagnetismError> {
+    let atomic_number = symbol as i16;
+    let oxidation = oxidation_state as i16;
+    let (l, electrons) = match (symbol.category(), symbol.block()) {
+        (ElementCategory::Lanthanide, _) => (AngularMomentum::F, atomic_number - 54 - oxidation),
+        (ElementCategory::Actinide, _) => (AngularMomentum::F, atomic_number - 86 - oxidation),
+        (_, Block::D) => {
+            let group = symbol.group().expect("d-block elements have a group") as i16;
+            (AngularMomentum::D, group - oxidation)
+        }
+        _ => return Ok(None),
+    };
+    let capacity = 2 * (2 * l.l() as i16 + 1);
+    if (0..=capacity).contains(&electrons) {
+        Ok(Some((l, electrons as u8)))
+    } else {
+        Err(MagnetismError::InvalidOxidationState {
+            symbol,
+            oxidation_state,
+        })
+    }
+}
+
+/// Unpaired electrons of the ion, by Hund's rule.
+pub fn unpaired_electrons(
+    symbol: ElementSymbol,
+    oxidation_state: i8,
+    state: SpinState,
+) -> Result<u8, MagnetismError> {
+    let Some((l, electrons)) = open_shell(symbol, oxidation_state)? else {
+        return Ok(0);
+    };
+    let unpaired = match (l, state) {
+        (AngularMomentum::D, SpinState::Low) => {
+            // t2g holds 6 electrons in 3 orbitals, eg 4 in 2.
+            let t2g = electrons.min(6);
+            let eg = electrons - t2g;
+            half_filled(t2g, 3) + half_filled(eg, 2)
+        }
+        _ => half_filled(electrons, 2 * l.l() + 1),
+    };
+    Ok(unpaired)
+}
+
+/// Unpaired electrons in `orbitals` degenerate orbitals.
+fn half_filled(electrons: u8, orbitals: u8) -> u8 {
+    if electrons <= orbitals {
+        electrons
+    } else {
+        2 * orbitals - electrons
+    }
+}
+
+/// Spin-only moment of the ion in Bohr magnetons.
+pub fn default_moment(
+    symbol: ElementSymbol,
+    oxidation_state: i8,
+    state: SpinState,
+) -> Result<f64, MagnetismError> {
+    unpaired_electrons(symbol, oxidation_state, state).map(f64::from)
+}
+
+/// Oxidation and spin states of the ions in a structure, by element or by
+/// species label. Species without an ion fall back to `Species::spin()`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MomentModel {
+    ions: BTreeMap<ElementSymbol, (i8, SpinState)>,
+    species_ions: BTreeMap<String, (i8, SpinState)>,
+}
+
+impl MomentModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ion of every species of `symbol` without an ion of its own.
+    pub fn ion(mut self, symbol: ElementSymbol, oxidation_state: i8, state: SpinState) -> Self {
+        self.ions.insert(symbol, (oxidation_state, state));
+        self
+    }
+
+    /// The ion of one species, e.g. `Fe2` for Fe2+ next to `Fe3` for Fe3+.
+    pub fn species_ion(mut self, species: &Species, oxidation_state: i8, state: SpinState) -> Self {
+        self.species_ions
+            .insert(species.label().to_string(), (oxidation_state, state));
+        self
+    }
+
+    /// Moment of `species`, from its ion or, without one, its spin in `table`.
+    pub fn moment<T: LookupElement>(
+        &self,
+        table: &T,
+        species: &Species,
+    ) -> Result<f64, MagnetismError> {
+        let symbol = species.element();
+        let ion = self
+            .species_ions
+            .get(species.label())
+            .or_else(|| self.ions.get(&symbol));
+        match ion {
+            Some(&(oxidation_state, state)) => default_moment(symbol, oxidation_state, state),
+            None => Ok(species.spin(table) as f64),
+        }
+    }
+}
+
+/// An atom in fractional coordinates with an optional signed spin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Site {
+    pub species: Species,
+    pub position: [f64; 3],
+    pub spin: Option<f64>,
+}
+
+impl Site {
+    /// A site of an element, e.g. `ElementSymbol::Fe`, or of a species
+    /// such as `Fe1`.
+    pub fn new(species: impl Into<Species>, position: [f64; 3]) -> Self {
+        Self {
+            species: species.into(),
+            position,
+            spin: None,
+        }
+    }
+
+    pub fn symbol(&self) -> ElementSymbol {
+        self.species.element()
+    }
+
+    pub fn with_spin(mut self, spin: f64) -> Self {
+        self.spin = Some(spin);
+        self
+    }
+}
+
+/// The `POSITIONS_FRAC` block. Sites with a spin get a `SPIN=` annotation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionsFrac {
+    sites: Vec<Site>,
+    precision: usize,
+}
+
+impl PositionsFrac {
+    pub fn new(sites: Vec<Site>) -> Self {
+        Self {
+            sites,
+            precision: 10,
+        }
+    }
+
+    /// Number of decimals for coordinates. Defaults to 10.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn sites(&self) -> &[Site] {
+        &self.sites
+    }
+
+    /// Give every site with a non-zero moment in `model` a spin, multiplied
+    /// by `sign(index, site)`, e.g. `-1.0` on one sublattice for an
+    /// antiferromagnetic guess. Spins of species without an ion come from
+    /// `table`.
+    pub fn assign_moments<T, F>(
+        mut self,
+        table: &T,
+        model: &MomentModel,
+        sign: F,
+    ) -> Result<Self, MagnetismError>
+    where
+        T: LookupElement,
+        F: Fn(usize, &Site) -> f64,
+    {
+        for (i, site) in self.sites.iter_mut().enumerate() {
+            let moment = model.moment(table, &site.species)?;
+            site.spin = (moment != 0.0).then(|| sign(i, site) * moment);
+        }
+        Ok(self)
+    }
+
+    /// Sum of the site spins, the value for the `SPIN` parameter.
+    pub fn total_spin(&self) -> f64 {
+        self.sites.iter().filter_map(|site| site.spin).sum()
+    }
+}
+
+impl Display for PositionsFrac {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "%BLOCK POSITIONS_FRAC")?;
+        for site in self.sites.iter() {
+            write!(f, "{:>8}", site.species)?;
+            for x in site.position {
+                write!(
+                    f,
+                    " {:>width$.precision$}",
+                    x,
+                    width = self.precision + 4,
+                    precision = self.precision
+                )?;
+            }
+            if let Some(spin) = site.spin {
+                write!(f, " SPIN={spin:.4}")?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "%ENDBLOCK POSITIONS_FRAC")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MagnetismError {
+    /// The oxidation state empties or overfills the open shell.
+    InvalidOxidationState {
+        symbol: ElementSymbol,
+        oxidation_state: i8,
+    },
+}
+
+impl Display for MagnetismError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MagnetismError::InvalidOxidationState {
+                symbol,
+                oxidation_state,
+            } => write!(
+                f,
+                "{symbol} cannot have oxidation state {oxidation_state:+}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MagnetismError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{AngularMomentum, ElementSymbol, ElementTable, Species},
+    };
+
+    use super::{
+        default_moment, open_shell, unpaired_electrons, MomentModel, PositionsFrac, Site, SpinState,
+    };
+
+    #[test]
+    fn test_hund_counting() {
+        use ElementSymbol::*;
+        use SpinState::*;
+        assert_eq!(open_shell(Fe, 3).unwrap(), Some((AngularMomentum::D, 5)));
+        assert_eq!(unpaired_electrons(Fe, 3, High).unwrap(), 5);
+        assert_eq!(unpaired_electrons(Fe, 3, Low).unwrap(), 1);
+        assert_eq!(unpaired_electrons(Fe, 2, High).unwrap(), 4);
+        assert_eq!(unpaired_electrons(Fe, 2, Low).unwrap(), 0);
+        assert_eq!(unpaired_electrons(Co, 3, Low).unwrap(), 0);
+        assert_eq!(unpaired_electrons(Ni, 2, Low).unwrap(), 2);
+        assert_eq!(unpaired_electrons(Cu, 2, High).unwrap(), 1);
+        assert_eq!(unpaired_electrons(Ru, 4, Low).unwrap(), 2);
+        assert_eq!(unpaired_electrons(Gd, 3, High).unwrap(), 7);
+        assert_eq!(unpaired_electrons(Eu, 2, High).unwrap(), 7);
+        assert_eq!(unpaired_electrons(Ce, 4, High).unwrap(), 0);
+        assert_eq!(unpaired_electrons(U, 4, High).unwrap(), 2);
+        assert_eq!(unpaired_electrons(O, -2, High).unwrap(), 0);
+        assert!(default_moment(Ti, 5, High).is_err());
+        assert!(default_moment(Zn, -1, High).is_err());
+    }
+
+    #[test]
+    fn test_positions_frac() {
+        let model = MomentModel::new().ion(ElementSymbol::Fe, 3, SpinState::High);
+        let sites = vec![
+            Site::new(ElementSymbol::Fe, [0.0, 0.0, 0.0]),
+            Site::new(ElementSymbol::Fe, [0.5, 0.5, 0.5]),
+            Site::new(ElementSymbol::O, [0.25, 0.25, 0.25]),
+        ];
+        let block = PositionsFrac::new(sites)
+            .precision(4)
+            .assign_moments(&ELEMENT_TABLE, &model, |_, site| {
+                if site.position[0] < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            })
+            .unwrap();
+        assert_eq!(
+            block.to_string(),
+            "%BLOCK POSITIONS_FRAC
+      Fe   0.0000   0.0000   0.0000 SPIN=5.0000
+      Fe   0.5000   0.5000   0.5000 SPIN=-5.0000
+       O   0.2500   0.2500   0.2500
+%ENDBLOCK POSITIONS_FRAC
+"
+        );
+        assert_eq!(block.total_spin(), 0.0);
+        let fractional =
+            PositionsFrac::new(vec![Site::new(ElementSymbol::Mn, [0.0; 3]).with_spin(3.5)]);
+        assert_eq!(fractional.total_spin(), 3.5);
+        // Without an ion, the spin of the table or the species is used.
+        let o = Species::from(ElementSymbol::O);
+        assert_eq!(MomentModel::new().moment(&ELEMENT_TABLE, &o).unwrap(), 0.0);
+        let table = ElementTable::builtin()
+            .with_override_str("test", "Element_info:\n  - symbol: O\n    spin: 2\n")
+            .unwrap();
+        assert_eq!(MomentModel::new().moment(&table, &o).unwrap(), 2.0);
+        let o1 = "O1".parse::<Species>().unwrap().with_spin(1);
+        assert_eq!(MomentModel::new().moment(&table, &o1).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_species_moments() {
+        let fe1: Species = "Fe1".parse().unwrap();
+        let fe2: Species = "Fe2".parse().unwrap();
+        let model = MomentModel::new()
+            .ion(ElementSymbol::Fe, 3, SpinState::High)
+            .species_ion(&fe2, 2, SpinState::Low);
+        let sites = vec![
+            Site::new(fe1.clone(), [0.0; 3]),
+            Site::new(fe2.clone(), [0.5; 3]),
+            Site::new(ElementSymbol::Fe, [0.25; 3]),
+        ];
+        let block = PositionsFrac::new(sites)
+            .precision(2)
+            .assign_moments(&ELEMENT_TABLE, &model, |_, _| 1.0)
+            .unwrap();
+        assert_eq!(
+            block.to_string(),
+            "%BLOCK POSITIONS_FRAC
+     Fe1   0.00   0.00   0.00 SPIN=5.0000
+     Fe2   0.50   0.50   0.50
+      Fe   0.25   0.25   0.25 SPIN=5.0000
+%ENDBLOCK POSITIONS_FRAC
+"
+        );
+        assert_eq!(block.sites()[1].symbol(), ElementSymbol::Fe);
+    }
+}
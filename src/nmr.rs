@@ -0,0 +1,292 @@
+//! Quadrupolar couplings from the electric field gradient (EFG) tensors of
+//! CASTEP NMR calculations.
+//!
+//! EFGs are in atomic units (Eh / (e a0^2)), as in the `efg` lines of a
+//! `.magres` file, and quadrupole moments in mb, so that
+//! `Cq = 0.2349647 * Q * Vzz` MHz.
+//!
+//! ```
+//! use castep_periodic_table::element::ElementSymbol;
+//! use castep_periodic_table::nmr::{MagresEfg, QuadrupolarCoupling};
+//!
+//! let efg: MagresEfg = "efg O 1 -1.0 0.0 0.0 0.0 -0.5 0.0 0.0 0.0 1.5".parse().unwrap();
+//! assert_eq!(efg.element().unwrap(), ElementSymbol::O);
+//! // 17O is the quadrupolar isotope of oxygen.
+//! let coupling = QuadrupolarCoupling::for_element(ElementSymbol::O, &efg.tensor).unwrap();
+//! assert_eq!(coupling.to_string(), "17O: Cq = -9.0156 MHz, eta = 0.3333");
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    element::{ElementSymbol, Species},
+    isotope::{isotopes, Isotope},
+};
+
+/// `Cq` in MHz of a 1 mb quadrupole moment in an EFG of 1 au, e/h times
+/// 10^-31 m^2 times Eh / (e a0^2).
+pub const CQ_MHZ_PER_MB_AU: f64 = 0.2349647;
+
+/// A symmetric electric field gradient tensor in atomic units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EfgTensor(pub [[f64; 3]; 3]);
+
+impl EfgTensor {
+    /// Eigenvalues `[Vxx, Vyy, Vzz]`, ordered `|Vzz| >= |Vyy| >= |Vxx|`.
+    pub fn principal_components(&self) -> [f64; 3] {
+        let mut values = symmetric_eigenvalues(&self.0);
+        values.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+        values
+    }
+
+    /// `(Vxx - Vyy) / Vzz`, between 0 and 1. Zero for a vanishing EFG.
+    pub fn asymmetry(&self) -> f64 {
+        let [xx, yy, zz] = self.principal_components();
+        if zz == 0.0 {
+            0.0
+        } else {
+            (xx - yy) / zz
+        }
+    }
+}
+
+/// Eigenvalues of a symmetric 3x3 matrix, by the trigonometric method.
+fn symmetric_eigenvalues(m: &[[f64; 3]; 3]) -> [f64; 3] {
+    let off_diagonal = m[0][1].powi(2) + m[0][2].powi(2) + m[1][2].powi(2);
+    if off_diagonal == 0.0 {
+        return [m[0][0], m[1][1], m[2][2]];
+    }
+    let q = (m[0][0] + m[1][1] + m[2][2]) / 3.0;
+    let p = (((m[0][0] - q).powi(2)
+        + (m[1][1] - q).powi(2)
+        + (m[2][2] - q).powi(2)
+        + 2.0 * off_diagonal)
+        / 6.0)
+        .sqrt();
+    // det((m - qI) / p) / 2
+    let b = |i: usize, j: usize| (m[i][j] - if i == j { q } else { 0.0 }) / p;
+    let det = b(0, 0) * (b(1, 1) * b(2, 2) - b(1, 2) * b(2, 1))
+        - b(0, 1) * (b(1, 0) * b(2, 2) - b(1, 2) * b(2, 0))
+        + b(0, 2) * (b(1, 0) * b(2, 1) - b(1, 1) * b(2, 0));
+    let phi = (det / 2.0).clamp(-1.0, 1.0).acos() / 3.0;
+    let largest = q + 2.0 * p * phi.cos();
+    let smallest = q + 2.0 * p * (phi + 2.0 * std::f64::consts::FRAC_PI_3).cos();
+    [smallest, 3.0 * q - largest - smallest, largest]
+}
+
+/// The `efg` line of a `.magres` file: a species label, the index of the
+/// atom within the species and the tensor, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MagresEfg {
+    pub label: String,
+    pub index: usize,
+    pub tensor: EfgTensor,
+}
+
+impl MagresEfg {
+    /// The element of the species label.
+    pub fn element(&self) -> Result<ElementSymbol, NmrError> {
+        self.label
+            .parse::<Species>()
+            .map(|species| species.element())
+            .map_err(|_| NmrError::InvalidEfgLine(self.label.clone()))
+    }
+}
+
+impl FromStr for MagresEfg {
+    type Err = NmrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || NmrError::InvalidEfgLine(s.trim().to_string());
+        let mut fields = s.split_whitespace();
+        if fields.next() != Some("efg") {
+            return Err(invalid());
+        }
+        let label = fields.next().ok_or_else(invalid)?.to_string();
+        let index = fields
+            .next()
+            .and_then(|index| index.parse().ok())
+            .ok_or_else(invalid)?;
+        let values = fields
+            .map(|value| value.parse::<f64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<f64>, _>>()?;
+        let values: [f64; 9] = values.try_into().map_err(|_| invalid())?;
+        let mut tensor = [[0.0; 3]; 3];
+        for (i, row) in tensor.iter_mut().enumerate() {
+            row.copy_from_slice(&values[3 * i..3 * i + 3]);
+        }
+        Ok(Self {
+            label,
+            index,
+            tensor: EfgTensor(tensor),
+        })
+    }
+}
+
+/// The most abundant isotope of `symbol` with a quadrupole moment, e.g.
+/// 2H, 17O or 63Cu.
+pub fn quadrupolar_isotope(symbol: ElementSymbol) -> Option<&'static Isotope> {
+    isotopes(symbol)
+        .iter()
+        .filter(|isotope| isotope.quadrupole_moment.is_some())
+        .max_by(|a, b| a.abundance.total_cmp(&b.abundance))
+}
+
+/// Quadrupolar coupling constant and asymmetry of a nucleus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuadrupolarCoupling {
+    pub isotope: &'static Isotope,
+    /// `Cq` in MHz.
+    pub cq: f64,
+    pub eta: f64,
+}
+
+impl QuadrupolarCoupling {
+    pub fn new(isotope: &'static Isotope, efg: &EfgTensor) -> Result<Self, NmrError> {
+        let q = isotope
+            .quadrupole_moment
+            .ok_or_else(|| NmrError::NotQuadrupolar(isotope.to_string()))?;
+        let [_, _, vzz] = efg.principal_components();
+        Ok(Self {
+            isotope,
+            cq: CQ_MHZ_PER_MB_AU * q * vzz,
+            eta: efg.asymmetry(),
+        })
+    }
+
+    /// Use the isotope given by `quadrupolar_isotope`.
+    pub fn for_element(symbol: ElementSymbol, efg: &EfgTensor) -> Result<Self, NmrError> {
+        let isotope = quadrupolar_isotope(symbol).ok_or(NmrError::NoQuadrupolarIsotope(symbol))?;
+        Self::new(isotope, efg)
+    }
+
+    /// Quadrupolar product `Cq * sqrt(1 + eta^2 / 3)` in MHz.
+    pub fn pq(&self) -> f64 {
+        self.cq * (1.0 + self.eta.powi(2) / 3.0).sqrt()
+    }
+}
+
+impl Display for QuadrupolarCoupling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: Cq = {:.4} MHz, eta = {:.4}",
+            self.isotope, self.cq, self.eta
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NmrError {
+    InvalidEfgLine(String),
+    /// The isotope has no quadrupole moment.
+    NotQuadrupolar(String),
+    NoQuadrupolarIsotope(ElementSymbol),
+}
+
+impl Display for NmrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NmrError::InvalidEfgLine(line) => write!(
+                f,
+                "invalid efg line `{line}`, expected `efg <label> <index>` and nine values"
+            ),
+            NmrError::NotQuadrupolar(isotope) => {
+                write!(f, "{isotope} has no quadrupole moment")
+            }
+            NmrError::NoQuadrupolarIsotope(symbol) => {
+                write!(f, "no quadrupolar isotope data for {symbol}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NmrError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        element::ElementSymbol,
+        isotope::{isotope, nmr_isotopes, parse_isotope},
+    };
+
+    use super::{quadrupolar_isotope, EfgTensor, MagresEfg, QuadrupolarCoupling};
+
+    #[test]
+    fn test_principal_components() {
+        // A diagonal tensor rotated by 30 degrees about z.
+        let (s, c) = std::f64::consts::FRAC_PI_6.sin_cos();
+        let (a, b) = (-0.2, -0.6);
+        let efg = EfgTensor([
+            [a * c * c + b * s * s, (a - b) * s * c, 0.0],
+            [(a - b) * s * c, a * s * s + b * c * c, 0.0],
+            [0.0, 0.0, 0.8],
+        ]);
+        let [xx, yy, zz] = efg.principal_components();
+        assert!((xx + 0.2).abs() < 1e-12);
+        assert!((yy + 0.6).abs() < 1e-12);
+        assert!((zz - 0.8).abs() < 1e-12);
+        assert!((efg.asymmetry() - 0.5).abs() < 1e-12);
+        assert_eq!(EfgTensor([[0.0; 3]; 3]).asymmetry(), 0.0);
+    }
+
+    #[test]
+    fn test_quadrupolar_coupling() {
+        assert_eq!(
+            quadrupolar_isotope(ElementSymbol::H).unwrap().mass_number,
+            2
+        );
+        assert_eq!(
+            quadrupolar_isotope(ElementSymbol::Cu).unwrap().mass_number,
+            63
+        );
+        assert!(quadrupolar_isotope(ElementSymbol::C).is_none());
+        assert!(quadrupolar_isotope(ElementSymbol::Sn).is_none());
+        assert_eq!(
+            quadrupolar_isotope(ElementSymbol::Ba).unwrap().mass_number,
+            137
+        );
+        let efg: MagresEfg = "efg Al:oct 2 0.1 0 0 0 0.1 0 0 0 -0.2".parse().unwrap();
+        assert_eq!(efg.index, 2);
+        let al = QuadrupolarCoupling::for_element(efg.element().unwrap(), &efg.tensor).unwrap();
+        assert!((al.cq - 0.2349647 * 146.6 * -0.2).abs() < 1e-9);
+        assert_eq!(al.eta, 0.0);
+        assert_eq!(al.pq(), al.cq);
+        assert!(QuadrupolarCoupling::new(parse_isotope("13C").unwrap(), &efg.tensor).is_err());
+        assert!(QuadrupolarCoupling::for_element(ElementSymbol::Si, &efg.tensor).is_err());
+        let zr = QuadrupolarCoupling::for_element(ElementSymbol::Zr, &efg.tensor).unwrap();
+        assert_eq!(zr.isotope.to_string(), "91Zr");
+        assert!((zr.cq - 0.2349647 * -176.0 * -0.2).abs() < 1e-9);
+        assert!("efg Al 1 0 0 0".parse::<MagresEfg>().is_err());
+        assert!("ms Al 1 0 0 0 0 0 0 0 0 0".parse::<MagresEfg>().is_err());
+    }
+
+    #[test]
+    fn test_nmr_data() {
+        let h = isotope(ElementSymbol::H, 1).unwrap();
+        // 1H resonates at about 400 MHz in 9.4 T.
+        assert!((h.larmor_frequency(9.4).unwrap() - 400.2).abs() < 0.1);
+        let active: Vec<u16> = nmr_isotopes(ElementSymbol::O)
+            .map(|i| i.mass_number)
+            .collect();
+        assert_eq!(active, [17]);
+        // Spin-1/2 nuclei of heavier elements.
+        for (symbol, mass_numbers) in [
+            (ElementSymbol::Se, vec![77]),
+            (ElementSymbol::Cd, vec![111, 113]),
+            (ElementSymbol::Sn, vec![115, 117, 119]),
+            (ElementSymbol::Te, vec![123, 125]),
+            (ElementSymbol::W, vec![183]),
+            (ElementSymbol::Pt, vec![195]),
+            (ElementSymbol::Hg, vec![199, 201]),
+        ] {
+            let active: Vec<u16> = nmr_isotopes(symbol).map(|i| i.mass_number).collect();
+            assert_eq!(active, mass_numbers, "{symbol}");
+        }
+        for notation in ["73Ge", "87Sr", "91Zr", "95Mo", "137Ba"] {
+            let isotope = parse_isotope(notation).unwrap();
+            assert!(isotope.is_nmr_active() && isotope.quadrupole_moment.is_some());
+        }
+        assert!(parse_isotope("17O").unwrap().spin.is_quadrupolar());
+    }
+}
@@ -0,0 +1,136 @@
+//! Helpers for keywords of a CASTEP `.param` file.
+//!
+//! ```
+//! use castep_periodic_table::data::ELEMENT_TABLE;
+//! use castep_periodic_table::element::{BasisPrecision, ElementSymbol};
+//! use castep_periodic_table::param::{cut_off_energy, cut_off_energy_line};
+//!
+//! let species = [ElementSymbol::Fe, ElementSymbol::O];
+//! assert_eq!(cut_off_energy(&ELEMENT_TABLE, species, BasisPrecision::Fine), Ok(480.0));
+//! assert_eq!(
+//!     cut_off_energy_line(&ELEMENT_TABLE, species, BasisPrecision::Fine).unwrap(),
+//!     "CUT_OFF_ENERGY : 480.0 eV"
+//! );
+//! ```
+
+use std::{borrow::Borrow, fmt::Display};
+
+use crate::element::{BasisPrecision, ElementSymbol, LookupElement};
+
+/// The `CUT_OFF_ENERGY`, in eV, CASTEP picks for `BASIS_PRECISION` with
+/// these species: the highest recommended cutoff among their potentials.
+pub fn cut_off_energy<T, I>(
+    table: &T,
+    species: I,
+    precision: BasisPrecision,
+) -> Result<f64, CutoffError>
+where
+    T: LookupElement,
+    I: IntoIterator,
+    I::Item: Borrow<ElementSymbol>,
+{
+    species
+        .into_iter()
+        .map(|symbol| {
+            let symbol = *symbol.borrow();
+            table
+                .get_by_symbol(symbol)
+                .cutoff_energy(precision)
+                .ok_or(CutoffError::UnknownCutoffs(symbol))
+        })
+        .reduce(|a, b| Ok(a?.max(b?)))
+        .unwrap_or(Err(CutoffError::NoSpecies))
+}
+
+/// The `CUT_OFF_ENERGY` line for a `.param` file.
+pub fn cut_off_energy_line<T, I>(
+    table: &T,
+    species: I,
+    precision: BasisPrecision,
+) -> Result<String, CutoffError>
+where
+    T: LookupElement,
+    I: IntoIterator,
+    I::Item: Borrow<ElementSymbol>,
+{
+    cut_off_energy(table, species, precision)
+        .map(|energy| format!("CUT_OFF_ENERGY : {energy:.1} eV"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutoffError {
+    NoSpecies,
+    /// The table has no cutoffs for the potential of the element.
+    UnknownCutoffs(ElementSymbol),
+}
+
+impl Display for CutoffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CutoffError::NoSpecies => f.write_str("no species to pick a cutoff energy for"),
+            CutoffError::UnknownCutoffs(symbol) => {
+                write!(f, "the cutoffs of the {symbol} potential are not known")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CutoffError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{BasisPrecision, ElementSymbol, ElementTable},
+    };
+
+    use super::{cut_off_energy, CutoffError};
+
+    #[test]
+    fn test_cut_off_energy() {
+        let species = [ElementSymbol::Si, ElementSymbol::O, ElementSymbol::H];
+        assert_eq!(
+            cut_off_energy(&ELEMENT_TABLE, species, BasisPrecision::Coarse),
+            Ok(310.0)
+        );
+        assert_eq!(
+            cut_off_energy(&ELEMENT_TABLE, species, BasisPrecision::Medium),
+            Ok(380.0)
+        );
+        let precise = cut_off_energy(&ELEMENT_TABLE, species, BasisPrecision::Precise).unwrap();
+        assert!((precise - 576.0).abs() < 1e-9);
+        assert_eq!(
+            cut_off_energy(
+                &ELEMENT_TABLE,
+                [] as [ElementSymbol; 0],
+                BasisPrecision::Fine
+            ),
+            Err(CutoffError::NoSpecies)
+        );
+        let ceria = [ElementSymbol::Ce, ElementSymbol::O];
+        assert_eq!(
+            cut_off_energy(&ELEMENT_TABLE, ceria, BasisPrecision::Fine),
+            Err(CutoffError::UnknownCutoffs(ElementSymbol::Ce))
+        );
+        let table = ElementTable::builtin()
+            .with_override_str(
+                "harder",
+                concat!(
+                    "Element_info:\n",
+                    "  - symbol: H\n",
+                    "    cutoffs: {coarse: 400, medium: 500, fine: 600}\n",
+                    "  - symbol: Ce\n",
+                    "    cutoffs: {coarse: 360, medium: 450, fine: 550}\n",
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            cut_off_energy(&table, species, BasisPrecision::Fine),
+            Ok(600.0)
+        );
+        assert_eq!(
+            cut_off_energy(&table, ceria, BasisPrecision::Fine),
+            Ok(550.0)
+        );
+    }
+}
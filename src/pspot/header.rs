@@ -0,0 +1,412 @@
+//! Reader for the headers of CASTEP pseudopotential files.
+//!
+//! Ultrasoft (`.usp`, `.uspcc`) and `.recpot` files generated by CASTEP
+//! start with a `START COMMENT` ... `END COMMENT` block describing the
+//! potential. The lines recognised in that block are
+//! ```text
+//! |  Element: C  Ionic charge:  4.00  Level of theory: PBE      |
+//! |              Reference Electronic Structure                 |
+//! |         Orbital         Occupation       Energy             |
+//! |            2s              2.000           -1.009           |
+//! |                                                             |
+//! |               Pseudopotential Definition                    |
+//! |       Beta    l   e(Ry)     Rc(au)   scheme   norm          |
+//! |         1     0   -1.009     1.400     qc       0           |
+//! |       loc     2    0.000     1.400     pn       0           |
+//! |                                                             |
+//! |  Suggested cutoffs (eV): coarse 240  medium 300  fine 370   |
+//! ```
+//! Other lines are ignored. Tables end at the first blank line.
+
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::element::{
+    AngularMomentum, Cutoffs, ElementSymbol, Functional, Occupation, OtfgNumber, PotentialFormat,
+};
+
+/// Core radius of one channel of the pseudopotential definition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoreRadius {
+    /// Index of the projector, `None` for the local channel.
+    pub beta: Option<u8>,
+    pub l: AngularMomentum,
+    /// Radius in bohr.
+    pub radius: f64,
+}
+
+/// What the header of a pseudopotential file says about it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PotentialHeader {
+    pub format: PotentialFormat,
+    pub element: ElementSymbol,
+    pub valence_charge: f64,
+    pub functional: Option<Functional>,
+    pub reference: Vec<Occupation>,
+    pub core_radii: Vec<CoreRadius>,
+    pub cutoffs: Option<Cutoffs>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    None,
+    Reference,
+    Definition,
+}
+
+impl PotentialHeader {
+    /// Read the header of the file at `path`. The format is taken from the
+    /// extension.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, HeaderError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|error| HeaderError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        Self::parse(path, &text)
+    }
+
+    /// Parse the contents of a file. `path` gives the format and is used in
+    /// errors.
+    pub fn parse<P: AsRef<Path>>(path: P, text: &str) -> Result<Self, HeaderError> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(PotentialFormat::from_extension)
+            .filter(|format| *format != PotentialFormat::Ncp)
+            .ok_or_else(|| HeaderError::UnsupportedFile(path.to_path_buf()))?;
+        let error = |line: usize, kind: HeaderErrorKind| HeaderError::Parse {
+            path: path.to_path_buf(),
+            line,
+            kind,
+        };
+
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .skip_while(|(_, line)| line.is_empty());
+        match lines.next() {
+            Some((_, "START COMMENT")) => (),
+            Some((number, _)) => return Err(error(number, HeaderErrorKind::MissingComment)),
+            None => return Err(error(1, HeaderErrorKind::MissingComment)),
+        }
+
+        let mut element = None;
+        let mut valence_charge = None;
+        let mut functional = None;
+        let mut reference = Vec::new();
+        let mut core_radii = Vec::new();
+        let mut cutoffs = None;
+        let mut section = Section::None;
+        let mut end = None;
+        for (number, line) in lines {
+            if line == "END COMMENT" {
+                end = Some(number);
+                break;
+            }
+            let content = line.trim_matches('|').trim();
+            if content.is_empty() {
+                section = Section::None;
+                continue;
+            }
+            if content.contains("Reference Electronic Structure") {
+                section = Section::Reference;
+                continue;
+            }
+            if content.contains("Pseudopotential Definition") {
+                section = Section::Definition;
+                continue;
+            }
+            if let Some(value) = value_after(content, "Element:") {
+                let symbol = ElementSymbol::from_str(value)
+                    .map_err(|_| error(number, HeaderErrorKind::invalid("element", value)))?;
+                element = Some(symbol);
+            }
+            if let Some(value) = value_after(content, "Ionic charge:") {
+                let charge = value
+                    .parse::<f64>()
+                    .map_err(|_| error(number, HeaderErrorKind::invalid("ionic charge", value)))?;
+                valence_charge = Some(charge);
+            }
+            if let Some(value) = value_after(content, "Level of theory:") {
+                let parsed = Functional::from_str(&value.to_ascii_uppercase()).map_err(|_| {
+                    error(number, HeaderErrorKind::invalid("level of theory", value))
+                })?;
+                functional = Some(parsed);
+            }
+            if content.starts_with("Suggested cutoffs") {
+                cutoffs = Some(parse_cutoffs(content).map_err(|kind| error(number, kind))?);
+                continue;
+            }
+            let row: Vec<&str> = content.split_whitespace().collect();
+            match section {
+                Section::Reference if starts_with_digit(row[0]) => {
+                    reference.push(parse_orbital(&row).map_err(|kind| error(number, kind))?);
+                }
+                Section::Definition if starts_with_digit(row[0]) || row[0] == "loc" => {
+                    core_radii.push(parse_channel(&row).map_err(|kind| error(number, kind))?);
+                }
+                _ => (),
+            }
+        }
+        let end = end.ok_or_else(|| {
+            error(
+                text.lines().count().max(1),
+                HeaderErrorKind::UnterminatedComment,
+            )
+        })?;
+        Ok(Self {
+            format,
+            element: element.ok_or_else(|| error(end, HeaderErrorKind::MissingField("element")))?,
+            valence_charge: valence_charge
+                .ok_or_else(|| error(end, HeaderErrorKind::MissingField("ionic charge")))?,
+            functional,
+            reference,
+            core_radii,
+            cutoffs,
+        })
+    }
+}
+
+/// The first word after `label`.
+fn value_after<'a>(content: &'a str, label: &str) -> Option<&'a str> {
+    content
+        .find(label)
+        .and_then(|start| content[start + label.len()..].split_whitespace().next())
+}
+
+fn starts_with_digit(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// A row like `2s  2.000  -1.009`.
+fn parse_orbital(row: &[&str]) -> Result<Occupation, HeaderErrorKind> {
+    let orbital = row[0];
+    let invalid = || HeaderErrorKind::invalid("orbital", orbital);
+    let letter_at = orbital
+        .find(|c: char| c.is_ascii_alphabetic())
+        .ok_or_else(invalid)?;
+    let n = orbital[..letter_at].parse::<u8>().map_err(|_| invalid())?;
+    let mut letters = orbital[letter_at..].chars();
+    let l = match (letters.next(), letters.next()) {
+        (Some(letter), None) => AngularMomentum::from_letter(letter).ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+    let occupation = row
+        .get(1)
+        .ok_or(HeaderErrorKind::MissingField("occupation"))?;
+    let electrons = OtfgNumber::from_str(occupation)
+        .map_err(|_| HeaderErrorKind::invalid("occupation", occupation))?;
+    Ok(Occupation::new(n, l, electrons))
+}
+
+/// A row like `1  0  -1.009  1.400  qc  0` or `loc  2  0.000  1.400  pn  0`.
+fn parse_channel(row: &[&str]) -> Result<CoreRadius, HeaderErrorKind> {
+    let beta = match row[0] {
+        "loc" => None,
+        beta => Some(
+            beta.parse::<u8>()
+                .map_err(|_| HeaderErrorKind::invalid("beta", beta))?,
+        ),
+    };
+    let l = row.get(1).ok_or(HeaderErrorKind::MissingField("l"))?;
+    let l = l
+        .parse::<u8>()
+        .ok()
+        .and_then(AngularMomentum::from_l)
+        .ok_or_else(|| HeaderErrorKind::invalid("l", l))?;
+    let radius = row.get(3).ok_or(HeaderErrorKind::MissingField("Rc"))?;
+    let radius = radius
+        .parse::<f64>()
+        .map_err(|_| HeaderErrorKind::invalid("Rc", radius))?;
+    Ok(CoreRadius { beta, l, radius })
+}
+
+/// A line like `Suggested cutoffs (eV): coarse 240  medium 300  fine 370`.
+fn parse_cutoffs(content: &str) -> Result<Cutoffs, HeaderErrorKind> {
+    let energy = |label: &'static str| {
+        let value = value_after(content, label).ok_or(HeaderErrorKind::MissingField(label))?;
+        value
+            .parse::<f64>()
+            .map_err(|_| HeaderErrorKind::invalid(label, value))
+    };
+    Ok(Cutoffs {
+        coarse: energy("coarse")?,
+        medium: energy("medium")?,
+        fine: energy("fine")?,
+    })
+}
+
+#[derive(Debug)]
+pub enum HeaderError {
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// Not a `.usp`, `.uspcc` or `.recpot` file.
+    UnsupportedFile(PathBuf),
+    Parse {
+        path: PathBuf,
+        /// 1-based line number.
+        line: usize,
+        kind: HeaderErrorKind,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderErrorKind {
+    /// The file does not start with `START COMMENT`.
+    MissingComment,
+    /// No `END COMMENT` before the end of the file.
+    UnterminatedComment,
+    MissingField(&'static str),
+    InvalidValue {
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl HeaderErrorKind {
+    fn invalid(field: &'static str, value: &str) -> Self {
+        HeaderErrorKind::InvalidValue {
+            field,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for HeaderErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeaderErrorKind::MissingComment => write!(f, "expected `START COMMENT`"),
+            HeaderErrorKind::UnterminatedComment => write!(f, "missing `END COMMENT`"),
+            HeaderErrorKind::MissingField(field) => write!(f, "missing {field}"),
+            HeaderErrorKind::InvalidValue { field, value } => {
+                write!(f, "invalid {field} `{value}`")
+            }
+        }
+    }
+}
+
+impl Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeaderError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            HeaderError::UnsupportedFile(path) => {
+                write!(f, "{}: not a usp, uspcc or recpot file", path.display())
+            }
+            HeaderError::Parse { path, line, kind } => {
+                write!(f, "{}:{}: {}", path.display(), line, kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HeaderError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::element::{AngularMomentum, Cutoffs, ElementSymbol, Functional, PotentialFormat};
+
+    use super::{HeaderError, HeaderErrorKind, PotentialHeader};
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn test_usp_header() {
+        let header = PotentialHeader::read(fixture("C_00PBE.usp")).unwrap();
+        assert_eq!(header.format, PotentialFormat::Usp);
+        assert_eq!(header.element, ElementSymbol::C);
+        assert_eq!(header.valence_charge, 4.0);
+        assert_eq!(header.functional, Some(Functional::Pbe));
+        let reference: Vec<String> = header.reference.iter().map(|o| o.to_string()).collect();
+        assert_eq!(reference, ["2s2.000", "2p2.000"]);
+        assert_eq!(header.core_radii.len(), 5);
+        assert_eq!(header.core_radii[4].beta, None);
+        assert_eq!(header.core_radii[4].l, AngularMomentum::D);
+        assert_eq!(header.core_radii[2].radius, 1.4);
+        assert_eq!(
+            header.cutoffs,
+            Some(Cutoffs {
+                coarse: 240.0,
+                medium: 300.0,
+                fine: 370.0
+            })
+        );
+        let fe = PotentialHeader::read(fixture("Fe_00PBE.uspcc")).unwrap();
+        assert_eq!(fe.format, PotentialFormat::Uspcc);
+        assert_eq!(fe.valence_charge, 8.0);
+        assert_eq!(fe.reference[0].l, AngularMomentum::D);
+    }
+
+    #[test]
+    fn test_recpot_header() {
+        let header = PotentialHeader::read(fixture("O_00.recpot")).unwrap();
+        assert_eq!(header.format, PotentialFormat::Recpot);
+        assert_eq!(header.element, ElementSymbol::O);
+        assert_eq!(header.functional, Some(Functional::Lda));
+        assert_eq!(header.core_radii.len(), 2);
+        assert_eq!(header.cutoffs, None);
+    }
+
+    #[test]
+    fn test_header_errors() {
+        let error = PotentialHeader::read(fixture("Si_broken.usp")).unwrap_err();
+        assert!(matches!(
+            &error,
+            HeaderError::Parse {
+                line: 4,
+                kind: HeaderErrorKind::InvalidValue {
+                    field: "ionic charge",
+                    ..
+                },
+                ..
+            }
+        ));
+        assert!(error
+            .to_string()
+            .ends_with("Si_broken.usp:4: invalid ionic charge `four`"));
+        let error = PotentialHeader::read(fixture("N_unterminated.usp")).unwrap_err();
+        assert!(matches!(
+            error,
+            HeaderError::Parse {
+                line: 9,
+                kind: HeaderErrorKind::UnterminatedComment,
+                ..
+            }
+        ));
+        let error = PotentialHeader::parse("Na_00PBE.usp", "\n  3.5  3.6\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Na_00PBE.usp:2: expected `START COMMENT`"
+        );
+        let error =
+            PotentialHeader::parse("Na.usp", "START COMMENT\n| Element: Na |\nEND COMMENT\n")
+                .unwrap_err();
+        assert_eq!(error.to_string(), "Na.usp:3: missing ionic charge");
+        assert!(matches!(
+            PotentialHeader::parse("Na.ncp", ""),
+            Err(HeaderError::UnsupportedFile(_))
+        ));
+        assert!(PotentialHeader::read(fixture("missing.usp")).is_err());
+    }
+}
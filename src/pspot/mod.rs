@@ -0,0 +1,10 @@
+//! Pseudopotential files on disk: reading their headers and checking that
+//! the potentials named in an element table are available.
+mod header;
+mod scan;
+
+pub use header::{CoreRadius, HeaderError, HeaderErrorKind, PotentialHeader};
+pub use scan::{
+    Availability, AvailabilityReport, IndexedPotential, PotentialIndex, PotentialStatus, ScanError,
+    PSPOT_DIR_VAR,
+};
@@ -0,0 +1,501 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::element::{
+    Classification, Element, ElementSymbol, Potential, PotentialFile, PotentialKind,
+};
+
+/// Environment variable naming the pseudopotential directory, as read by
+/// CASTEP.
+pub const PSPOT_DIR_VAR: &str = "CASTEP_PSPOT_DIR";
+
+/// A pseudopotential file found on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedPotential {
+    pub path: PathBuf,
+    pub file: PotentialFile,
+    pub element: ElementSymbol,
+}
+
+impl IndexedPotential {
+    pub fn name(&self) -> &str {
+        self.file.name()
+    }
+
+    pub fn kind(&self) -> PotentialKind {
+        self.file.kind()
+    }
+}
+
+/// Pseudopotential files in a directory, indexed by element. The element
+/// is taken from the leading letters of the file name, e.g. `Fe` in
+/// `Fe_00PBE.uspcc` or `fe-optgga1.recpot`.
+///
+/// CASTEP only reads files directly in the directory, so potentials found in
+/// subdirectories by `scan_recursive` are only offered as alternatives, or
+/// flagged as duplicates when they share a name with the expected file.
+/// # Example
+/// ```no_run
+/// use castep_periodic_table::data::ELEMENT_TABLE;
+/// use castep_periodic_table::pspot::PotentialIndex;
+///
+/// let index = PotentialIndex::from_env().unwrap();
+/// let report = index.check(&ELEMENT_TABLE);
+/// if !report.is_complete() {
+///     eprint!("{report}");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PotentialIndex {
+    root: PathBuf,
+    potentials: BTreeMap<ElementSymbol, Vec<IndexedPotential>>,
+    unrecognised: Vec<PathBuf>,
+}
+
+impl PotentialIndex {
+    /// Index the files in `dir`. Files without a pseudopotential extension
+    /// are skipped.
+    pub fn scan<P: AsRef<Path>>(dir: P) -> Result<Self, ScanError> {
+        Self::scan_with_depth(dir.as_ref(), false)
+    }
+
+    /// Index `dir` and its subdirectories. Symbolic links to directories
+    /// are not followed.
+    pub fn scan_recursive<P: AsRef<Path>>(dir: P) -> Result<Self, ScanError> {
+        Self::scan_with_depth(dir.as_ref(), true)
+    }
+
+    fn scan_with_depth(dir: &Path, recursive: bool) -> Result<Self, ScanError> {
+        let root = dir.to_path_buf();
+        let mut files = Vec::new();
+        collect_files(&root, recursive, &mut files)?;
+        files.sort();
+        let mut index = Self {
+            root,
+            potentials: BTreeMap::new(),
+            unrecognised: Vec::new(),
+        };
+        for path in files {
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Ok(file) = PotentialFile::from_str(file_name) else {
+                continue;
+            };
+            match element_of(file_name) {
+                Some(element) => {
+                    index
+                        .potentials
+                        .entry(element)
+                        .or_default()
+                        .push(IndexedPotential {
+                            path,
+                            file,
+                            element,
+                        })
+                }
+                None => index.unrecognised.push(path),
+            }
+        }
+        Ok(index)
+    }
+
+    /// Index the directory named by `CASTEP_PSPOT_DIR`.
+    pub fn from_env() -> Result<Self, ScanError> {
+        let dir = env::var_os(PSPOT_DIR_VAR).ok_or(ScanError::NotSet)?;
+        Self::scan(dir)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Every potential of `symbol`, ordered by path.
+    pub fn potentials(&self, symbol: ElementSymbol) -> &[IndexedPotential] {
+        self.potentials.get(&symbol).map_or(&[], |found| found)
+    }
+
+    pub fn of_kind(
+        &self,
+        symbol: ElementSymbol,
+        kind: PotentialKind,
+    ) -> impl Iterator<Item = &IndexedPotential> {
+        self.potentials(symbol)
+            .iter()
+            .filter(move |potential| potential.kind() == kind)
+    }
+
+    /// Potential files whose names do not start with an element symbol.
+    pub fn unrecognised(&self) -> &[PathBuf] {
+        &self.unrecognised
+    }
+
+    /// Potentials of one element and kind found more than once, e.g. the
+    /// same file name in a subdirectory, or `C_00PBE.usp` next to
+    /// `C_00.usp`. Each group is ordered by path.
+    pub fn duplicates(&self) -> Vec<Vec<&IndexedPotential>> {
+        let mut groups: Vec<Vec<&IndexedPotential>> = Vec::new();
+        for potentials in self.potentials.values() {
+            let start = groups.len();
+            for potential in potentials {
+                match groups[start..]
+                    .iter_mut()
+                    .find(|group| group[0].kind() == potential.kind())
+                {
+                    Some(group) => group.push(potential),
+                    None => groups.push(vec![potential]),
+                }
+            }
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Whether CASTEP reads `potential`, i.e. it is not in a subdirectory.
+    pub fn is_top_level(&self, potential: &IndexedPotential) -> bool {
+        potential.path.parent() == Some(self.root.as_path())
+    }
+
+    /// Check the potential of every element in `table`.
+    pub fn check<T: Classification>(&self, table: &T) -> AvailabilityReport {
+        self.check_elements(table.elements().iter())
+    }
+
+    /// Check the potentials of `species` only.
+    pub fn check_species<T: Classification>(
+        &self,
+        table: &T,
+        species: &[ElementSymbol],
+    ) -> AvailabilityReport {
+        self.check_elements(
+            table
+                .elements()
+                .iter()
+                .filter(|elm| species.contains(&elm.symbol())),
+        )
+    }
+
+    fn check_elements<'a>(
+        &self,
+        elements: impl Iterator<Item = &'a Element>,
+    ) -> AvailabilityReport {
+        let entries = elements
+            .map(|elm| {
+                let candidates = self.potentials(elm.symbol());
+                let matches: Vec<&IndexedPotential> = match elm.potential() {
+                    Potential::File(file) => candidates
+                        .iter()
+                        .filter(|potential| potential.name() == file.name())
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                let found = matches
+                    .iter()
+                    .find(|potential| self.is_top_level(potential));
+                let status = match (elm.potential(), found) {
+                    (Potential::File(_), Some(found)) if matches.len() > 1 => {
+                        // The file CASTEP reads first, then the copies.
+                        let copies = matches.iter().filter(|copy| *copy != found);
+                        PotentialStatus::Duplicated(
+                            std::iter::once(found)
+                                .chain(copies)
+                                .map(|potential| potential.path.clone())
+                                .collect(),
+                        )
+                    }
+                    (Potential::File(_), Some(found)) => PotentialStatus::Found(found.path.clone()),
+                    (Potential::File(_), None) => PotentialStatus::Missing,
+                    _ => PotentialStatus::OnTheFly,
+                };
+                let alternatives = candidates
+                    .iter()
+                    .filter(|potential| found.is_none() || !matches.contains(potential))
+                    .map(|potential| potential.path.clone())
+                    .collect();
+                Availability {
+                    symbol: elm.symbol(),
+                    potential: elm.potential().clone(),
+                    status,
+                    alternatives,
+                }
+            })
+            .collect();
+        AvailabilityReport { entries }
+    }
+}
+
+fn collect_files(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> Result<(), ScanError> {
+    let io_error = |error| ScanError::Io {
+        path: dir.to_path_buf(),
+        error,
+    };
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        // `symlink_metadata` so that a link back to a parent is not a loop.
+        let is_dir = fs::symlink_metadata(&path).map_err(io_error)?.is_dir();
+        if !is_dir {
+            files.push(path);
+        } else if recursive {
+            collect_files(&path, recursive, files)?;
+        }
+    }
+    Ok(())
+}
+
+/// Element from the leading letters of a file name, in any case.
+fn element_of(file_name: &str) -> Option<ElementSymbol> {
+    let letters = file_name
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .map_or(file_name, |end| &file_name[..end]);
+    let mut chars = letters.chars();
+    let first = chars.next()?.to_ascii_uppercase();
+    let symbol: String = std::iter::once(first)
+        .chain(chars.map(|c| c.to_ascii_lowercase()))
+        .collect();
+    ElementSymbol::from_str(&symbol).ok()
+}
+
+/// Whether the potential an element asks for is on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PotentialStatus {
+    Found(PathBuf),
+    /// Found in the directory and again in a subdirectory. The file CASTEP
+    /// reads comes first.
+    Duplicated(Vec<PathBuf>),
+    /// Not in the directory itself. Copies in subdirectories are listed as
+    /// alternatives.
+    Missing,
+    /// OTFG definitions and libraries need no file.
+    OnTheFly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Availability {
+    pub symbol: ElementSymbol,
+    pub potential: Potential,
+    pub status: PotentialStatus,
+    /// Other potentials of the element found on disk, including copies of
+    /// the expected file in subdirectories when it is missing.
+    pub alternatives: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AvailabilityReport {
+    pub entries: Vec<Availability>,
+}
+
+impl AvailabilityReport {
+    pub fn missing(&self) -> impl Iterator<Item = &Availability> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.status == PotentialStatus::Missing)
+    }
+
+    pub fn duplicated(&self) -> impl Iterator<Item = &Availability> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.status, PotentialStatus::Duplicated(_)))
+    }
+
+    /// Every file-based potential is found exactly once.
+    pub fn is_complete(&self) -> bool {
+        self.missing().next().is_none() && self.duplicated().next().is_none()
+    }
+}
+
+fn join_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl Display for Availability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:>8}  {}: ", self.symbol, self.potential)?;
+        match &self.status {
+            PotentialStatus::Found(path) => write!(f, "found {}", path.display())?,
+            PotentialStatus::Duplicated(paths) => write!(f, "duplicated {}", join_paths(paths))?,
+            PotentialStatus::Missing => f.write_str("missing")?,
+            PotentialStatus::OnTheFly => f.write_str("generated on the fly")?,
+        }
+        if !self.alternatives.is_empty() {
+            write!(f, " (alternatives: {})", join_paths(&self.alternatives))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for AvailabilityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in self.entries.iter() {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ScanError {
+    /// `CASTEP_PSPOT_DIR` is not set.
+    NotSet,
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanError::NotSet => write!(f, "{PSPOT_DIR_VAR} is not set"),
+            ScanError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScanError::NotSet => None,
+            ScanError::Io { error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{
+        data::ELEMENT_TABLE,
+        element::{ElementSymbol, ElementTable, PotentialKind},
+    };
+
+    use super::{PotentialIndex, PotentialStatus};
+
+    fn fixture_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pspot")
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = fixture_dir();
+        let index = PotentialIndex::scan(&dir).unwrap();
+        let names: Vec<&str> = index
+            .potentials(ElementSymbol::O)
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(names, ["O_00PBE.usp", "o-optgga1.recpot"]);
+        assert_eq!(
+            index
+                .of_kind(ElementSymbol::O, PotentialKind::NormConserving)
+                .count(),
+            1
+        );
+        assert_eq!(index.unrecognised(), [dir.join("Xx_00PBE.usp")]);
+        // The LDA and PBE carbon potentials are both ultrasoft.
+        let duplicates: Vec<Vec<&str>> = index
+            .duplicates()
+            .iter()
+            .map(|group| group.iter().map(|p| p.name()).collect())
+            .collect();
+        assert_eq!(duplicates, [["C_00.usp", "C_00PBE.usp"]]);
+
+        let species = [ElementSymbol::C, ElementSymbol::O, ElementSymbol::Fe];
+        let report = index.check_species(&ELEMENT_TABLE, &species);
+        assert!(!report.is_complete());
+        assert_eq!(
+            report.entries[0].status,
+            PotentialStatus::Found(dir.join("C_00PBE.usp"))
+        );
+        assert_eq!(report.entries[0].alternatives, [dir.join("C_00.usp")]);
+        assert_eq!(
+            report.entries[1].status,
+            PotentialStatus::Found(dir.join("O_00PBE.usp"))
+        );
+        assert_eq!(
+            report.entries[1].alternatives,
+            [dir.join("o-optgga1.recpot")]
+        );
+        let missing: Vec<ElementSymbol> = report.missing().map(|entry| entry.symbol).collect();
+        assert_eq!(missing, [ElementSymbol::Fe]);
+        assert_eq!(report.entries[2].alternatives, [dir.join("Fe_C19.usp")]);
+
+        let table = ElementTable::builtin()
+            .with_override_str(
+                "site",
+                "Element_info:\n  - symbol: Fe\n    potential: Fe_C19.usp\n  - symbol: O\n    potential: C19\n",
+            )
+            .unwrap();
+        let report = index.check_species(&table, &species);
+        assert!(report.is_complete());
+        assert_eq!(report.entries[1].status, PotentialStatus::OnTheFly);
+        assert!(report.entries[2].alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_scan_recursive() {
+        let dir = fixture_dir();
+        let index = PotentialIndex::scan_recursive(&dir).unwrap();
+        let o = index.potentials(ElementSymbol::O);
+        assert_eq!(o.len(), 3);
+        assert!(!index.is_top_level(&o[1]));
+        let duplicates: Vec<Vec<&std::path::Path>> = index
+            .duplicates()
+            .iter()
+            .map(|group| group.iter().map(|p| p.path.as_path()).collect())
+            .collect();
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(
+            duplicates[1],
+            [dir.join("O_00PBE.usp"), dir.join("extra/O_00PBE.usp")]
+        );
+        // CASTEP reads the top-level file, but the copy is flagged.
+        let report = index.check_species(&ELEMENT_TABLE, &[ElementSymbol::O]);
+        assert_eq!(
+            report.entries[0].status,
+            PotentialStatus::Duplicated(vec![
+                dir.join("O_00PBE.usp"),
+                dir.join("extra/O_00PBE.usp")
+            ])
+        );
+        assert_eq!(
+            report.entries[0].alternatives,
+            [dir.join("o-optgga1.recpot")]
+        );
+        assert!(!report.is_complete());
+        assert!(report.to_string().contains("duplicated "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_symlink_loop() {
+        let dir = std::env::temp_dir().join(format!("pspot-loop-{}", std::process::id()));
+        let nested = dir.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::copy(
+            fixture_dir().join("C_00PBE.usp"),
+            nested.join("C_00PBE.usp"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&dir, nested.join("parent")).unwrap();
+        let index = PotentialIndex::scan_recursive(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let index = index.unwrap();
+        assert_eq!(index.potentials(ElementSymbol::C).len(), 1);
+    }
+
+    #[test]
+    fn test_scan_missing_dir() {
+        assert!(PotentialIndex::scan(fixture_dir().join("nowhere")).is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Named sets of atomic radii: covalent radii for single, double and triple
+//! bonds, van der Waals, metallic and Shannon ionic radii.
+//!
+//! The values are in `radii.yaml`, next to `element_table.yaml`, which
+//! lists the source of each set. `Element::covalent_radius()` is the
+//! `CovalentSingle` set.
+//!
+//! ```
+//! use castep_periodic_table::element::ElementSymbol;
+//! use castep_periodic_table::magnetism::SpinState;
+//! use castep_periodic_table::radius::{ionic_radius, radius, LengthUnit, RadiusKind};
+//!
+//! let double = radius(ElementSymbol::C, RadiusKind::CovalentDouble).unwrap();
+//! assert_eq!(double.angstrom(), 0.67);
+//! assert_eq!(double.in_unit(LengthUnit::Picometre), 67.0);
+//! assert!(double.source().starts_with("Pyykko"));
+//!
+//! let fe = ionic_radius(ElementSymbol::Fe, 2, 6, Some(SpinState::Low)).unwrap();
+//! assert_eq!(fe.angstrom(), 0.61);
+//! assert!(radius(ElementSymbol::Lr, RadiusKind::VdwBondi).is_none());
+//! ```
+
+use std::{fmt::Display, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::{element::ElementSymbol, magnetism::SpinState};
+
+const RADII_YAML: &str = include_str!("../radii.yaml");
+
+const BOHR_IN_ANGSTROM: f64 = 0.529177210903;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    Angstrom,
+    Picometre,
+    Bohr,
+}
+
+impl LengthUnit {
+    /// Convert a length in angstrom to this unit.
+    pub fn convert_angstrom(&self, length: f64) -> f64 {
+        match self {
+            LengthUnit::Angstrom => length,
+            LengthUnit::Picometre => length * 100.0,
+            LengthUnit::Bohr => length / BOHR_IN_ANGSTROM,
+        }
+    }
+}
+
+impl Display for LengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LengthUnit::Angstrom => f.write_str("ang"),
+            LengthUnit::Picometre => f.write_str("pm"),
+            LengthUnit::Bohr => f.write_str("bohr"),
+        }
+    }
+}
+
+/// A set of radii.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RadiusKind {
+    /// Pyykko single-bond covalent radii, for every element.
+    CovalentSingle,
+    /// Pyykko double-bond covalent radii.
+    CovalentDouble,
+    /// Pyykko triple-bond covalent radii.
+    CovalentTriple,
+    /// Cordero covalent radii, from bond lengths in crystal structures.
+    CovalentCordero,
+    VdwBondi,
+    VdwAlvarez,
+    /// Metallic radii for 12-coordination.
+    Metallic,
+    /// Shannon effective ionic radii.
+    Ionic {
+        charge: i8,
+        coordination: u8,
+    },
+}
+
+impl RadiusKind {
+    /// The sets without parameters.
+    pub const ATOMIC: [RadiusKind; 7] = [
+        RadiusKind::CovalentSingle,
+        RadiusKind::CovalentDouble,
+        RadiusKind::CovalentTriple,
+        RadiusKind::CovalentCordero,
+        RadiusKind::VdwBondi,
+        RadiusKind::VdwAlvarez,
+        RadiusKind::Metallic,
+    ];
+
+    pub fn source(&self) -> &'static str {
+        match self {
+            RadiusKind::CovalentSingle => "Pyykko & Atsumi, Chem. Eur. J. 15, 186 (2009)",
+            RadiusKind::CovalentDouble => "Pyykko & Atsumi, Chem. Eur. J. 15, 12770 (2009)",
+            RadiusKind::CovalentTriple => {
+                "Pyykko, Riedel & Patzschke, Chem. Eur. J. 11, 3511 (2005)"
+            }
+            RadiusKind::CovalentCordero => "Cordero et al., Dalton Trans. 2832 (2008)",
+            RadiusKind::VdwBondi => "Bondi, J. Phys. Chem. 68, 441 (1964)",
+            RadiusKind::VdwAlvarez => "Alvarez, Dalton Trans. 42, 8617 (2013)",
+            RadiusKind::Metallic => {
+                "Greenwood & Earnshaw, Chemistry of the Elements, 2nd ed. (1997)"
+            }
+            RadiusKind::Ionic { .. } => "Shannon, Acta Cryst. A32, 751 (1976)",
+        }
+    }
+}
+
+impl Display for RadiusKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RadiusKind::CovalentSingle => f.write_str("single-bond covalent"),
+            RadiusKind::CovalentDouble => f.write_str("double-bond covalent"),
+            RadiusKind::CovalentTriple => f.write_str("triple-bond covalent"),
+            RadiusKind::CovalentCordero => f.write_str("covalent (Cordero)"),
+            RadiusKind::VdwBondi => f.write_str("van der Waals (Bondi)"),
+            RadiusKind::VdwAlvarez => f.write_str("van der Waals (Alvarez)"),
+            RadiusKind::Metallic => f.write_str("metallic"),
+            RadiusKind::Ionic {
+                charge,
+                coordination,
+            } => write!(f, "ionic, charge {charge:+}, coordination {coordination}"),
+        }
+    }
+}
+
+/// A radius of one element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Radius {
+    pub symbol: ElementSymbol,
+    pub kind: RadiusKind,
+    /// The spin state of ionic radii given for high and low spin.
+    pub spin: Option<SpinState>,
+    angstrom: f64,
+}
+
+impl Radius {
+    pub fn angstrom(&self) -> f64 {
+        self.angstrom
+    }
+
+    pub fn in_unit(&self, unit: LengthUnit) -> f64 {
+        unit.convert_angstrom(self.angstrom)
+    }
+
+    pub fn source(&self) -> &'static str {
+        self.kind.source()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AtomicEntry {
+    symbol: ElementSymbol,
+    single: Option<f64>,
+    double: Option<f64>,
+    triple: Option<f64>,
+    cordero: Option<f64>,
+    bondi: Option<f64>,
+    alvarez: Option<f64>,
+    metallic: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SpinEntry {
+    High,
+    Low,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IonicEntry {
+    symbol: ElementSymbol,
+    charge: i8,
+    coordination: u8,
+    spin: Option<SpinEntry>,
+    radius: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct RadiiYaml {
+    #[serde(rename = "Radii")]
+    atomic: Vec<AtomicEntry>,
+    #[serde(rename = "Ionic")]
+    ionic: Vec<IonicEntry>,
+}
+
+/// All radii of `radii.yaml`, ordered by element.
+fn all_radii() -> &'static [Radius] {
+    static RADII: OnceLock<Vec<Radius>> = OnceLock::new();
+    RADII.get_or_init(|| {
+        let parsed: RadiiYaml = serde_yaml::from_str(RADII_YAML).expect("radii.yaml is valid");
+        let mut radii = Vec::new();
+        for entry in parsed.atomic {
+            let values = [
+                entry.single,
+                entry.double,
+                entry.triple,
+                entry.cordero,
+                entry.bondi,
+                entry.alvarez,
+                entry.metallic,
+            ];
+            for (kind, value) in RadiusKind::ATOMIC.into_iter().zip(values) {
+                if let Some(angstrom) = value {
+                    radii.push(Radius {
+                        symbol: entry.symbol,
+                        kind,
+                        spin: None,
+                        angstrom,
+                    });
+                }
+            }
+        }
+        for entry in parsed.ionic {
+            radii.push(Radius {
+                symbol: entry.symbol,
+                kind: RadiusKind::Ionic {
+                    charge: entry.charge,
+                    coordination: entry.coordination,
+                },
+                spin: entry.spin.map(|spin| match spin {
+                    SpinEntry::High => SpinState::High,
+                    SpinEntry::Low => SpinState::Low,
+                }),
+                angstrom: entry.radius,
+            });
+        }
+        radii.sort_by_key(|radius| radius.symbol);
+        radii
+    })
+}
+
+/// Every radius of `symbol`.
+pub fn radii(symbol: ElementSymbol) -> &'static [Radius] {
+    let all = all_radii();
+    let start = all.partition_point(|radius| radius.symbol < symbol);
+    let end = all.partition_point(|radius| radius.symbol <= symbol);
+    &all[start..end]
+}
+
+/// The radius of `symbol` in a set. For ionic radii given for both spin
+/// states, the high-spin one.
+pub fn radius(symbol: ElementSymbol, kind: RadiusKind) -> Option<Radius> {
+    radii(symbol)
+        .iter()
+        .filter(|radius| radius.kind == kind)
+        .find(|radius| radius.spin != Some(SpinState::Low))
+        .copied()
+}
+
+/// Shannon radius of an ion. `spin` selects between high- and low-spin
+/// values and is ignored for ions with one value.
+pub fn ionic_radius(
+    symbol: ElementSymbol,
+    charge: i8,
+    coordination: u8,
+    spin: Option<SpinState>,
+) -> Option<Radius> {
+    let kind = RadiusKind::Ionic {
+        charge,
+        coordination,
+    };
+    let mut candidates = radii(symbol).iter().filter(|radius| radius.kind == kind);
+    match spin {
+        Some(spin) => candidates.find(|radius| radius.spin.is_none_or(|s| s == spin)),
+        None => candidates.find(|radius| radius.spin != Some(SpinState::Low)),
+    }
+    .copied()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{data::ELEMENT_TABLE, element::ElementSymbol, magnetism::SpinState};
+
+    use super::{ionic_radius, radii, radius, LengthUnit, RadiusKind};
+
+    #[test]
+    fn test_sets() {
+        for elm in ELEMENT_TABLE.iter() {
+            let single = radius(elm.symbol(), RadiusKind::CovalentSingle).unwrap();
+            assert_eq!(
+                elm.covalent_radius(),
+                Some(single.angstrom()),
+                "{}",
+                elm.symbol()
+            );
+        }
+        let c = ElementSymbol::C;
+        let single = radius(c, RadiusKind::CovalentSingle).unwrap().angstrom();
+        let double = radius(c, RadiusKind::CovalentDouble).unwrap().angstrom();
+        let triple = radius(c, RadiusKind::CovalentTriple).unwrap().angstrom();
+        assert!(single > double && double > triple);
+        assert!(radius(ElementSymbol::H, RadiusKind::CovalentDouble).is_none());
+        assert_eq!(
+            radius(ElementSymbol::Fe, RadiusKind::Metallic)
+                .unwrap()
+                .angstrom(),
+            1.26
+        );
+        let bohr = radius(ElementSymbol::H, RadiusKind::VdwBondi)
+            .unwrap()
+            .in_unit(LengthUnit::Bohr);
+        assert!((bohr - 2.2677).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ionic() {
+        let o = ionic_radius(ElementSymbol::O, -2, 6, None).unwrap();
+        assert_eq!(o.angstrom(), 1.40);
+        assert!(o.source().starts_with("Shannon"));
+        let high = ionic_radius(ElementSymbol::Co, 3, 6, None).unwrap();
+        assert_eq!(high.spin, Some(SpinState::High));
+        let low = ionic_radius(ElementSymbol::Co, 3, 6, Some(SpinState::Low)).unwrap();
+        assert_eq!(low.angstrom(), 0.545);
+        // Ions with one value ignore the spin state.
+        assert!(ionic_radius(ElementSymbol::Ni, 2, 6, Some(SpinState::Low)).is_some());
+        assert!(ionic_radius(ElementSymbol::O, -2, 5, None).is_none());
+        assert_eq!(
+            radii(ElementSymbol::Fe)
+                .iter()
+                .filter(|r| matches!(r.kind, RadiusKind::Ionic { .. }))
+                .count(),
+            5
+        );
+        assert_eq!(
+            RadiusKind::Ionic {
+                charge: -2,
+                coordination: 6
+            }
+            .to_string(),
+            "ionic, charge -2, coordination 6"
+        );
+    }
+}